@@ -0,0 +1,150 @@
+#ifndef XIV_EMOTE_PARSER_H
+#define XIV_EMOTE_PARSER_H
+
+/* Generated from src/ffi.rs by cbindgen, do not edit */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#define XIV_EMOTE_GENDER_MALE 0
+
+#define XIV_EMOTE_GENDER_FEMALE 1
+
+typedef enum XivEmoteStatus {
+  XIV_EMOTE_STATUS_OK = 0,
+  /**
+   * a required pointer argument was null
+   */
+  XIV_EMOTE_STATUS_NULL_ARGUMENT,
+  /**
+   * a string argument was not valid UTF-8
+   */
+  XIV_EMOTE_STATUS_INVALID_UTF8,
+  /**
+   * an unknown language code or gender
+   */
+  XIV_EMOTE_STATUS_INVALID_ARGUMENT,
+  /**
+   * the repository json could not be read
+   */
+  XIV_EMOTE_STATUS_INVALID_JSON,
+  /**
+   * no emote has the command
+   */
+  XIV_EMOTE_STATUS_NOT_FOUND,
+  /**
+   * the emote has no log messages in the language
+   */
+  XIV_EMOTE_STATUS_NO_LOG_MESSAGES,
+  /**
+   * both characters are self but are not the same character
+   */
+  XIV_EMOTE_STATUS_MULTIPLE_SELVES,
+  /**
+   * see [EmoteTextError::ParseError]
+   */
+  XIV_EMOTE_STATUS_PARSE_ERROR,
+  /**
+   * see [EmoteTextError::AstError]
+   */
+  XIV_EMOTE_STATUS_AST_ERROR,
+  /**
+   * see [EmoteTextError::MessageParseError]
+   */
+  XIV_EMOTE_STATUS_MESSAGE_PARSE_ERROR,
+  /**
+   * see [EmoteTextError::ProcessError]
+   */
+  XIV_EMOTE_STATUS_PROCESS_ERROR,
+  /**
+   * see [EmoteTextError::DecodeError]
+   */
+  XIV_EMOTE_STATUS_DECODE_ERROR,
+  /**
+   * the rendered message contained a null character
+   */
+  XIV_EMOTE_STATUS_INTERIOR_NUL,
+  /**
+   * any other error, including a panic inside the library
+   */
+  XIV_EMOTE_STATUS_OTHER,
+} XivEmoteStatus;
+
+/**
+ * Opaque handle to a repository.
+ */
+typedef struct XivEmoteRepository XivEmoteRepository;
+
+/**
+ * A character of a message, where the name is a null terminated UTF-8 string and the gender
+ * is one of the `XIV_EMOTE_GENDER` constants.
+ */
+typedef struct XivEmoteCharacter {
+  const char *name;
+  uint8_t gender;
+  bool is_pc;
+  bool is_self;
+} XivEmoteCharacter;
+
+/**
+ * Loads a repository from json, see [LogMessageRepository::from_json]. On success, the
+ * repository is written to `out` and must be freed with [xiv_emote_repository_free].
+ *
+ * # Safety
+ * `json` must be a valid null terminated string and `out` must be valid for writes.
+ */
+enum XivEmoteStatus xiv_emote_repository_from_json(const char *json,
+                                                   struct XivEmoteRepository **out);
+
+/**
+ * Frees a repository, doing nothing if it is null.
+ *
+ * # Safety
+ * `repo` must be null or a repository from [xiv_emote_repository_from_json] which has not
+ * already been freed.
+ */
+void xiv_emote_repository_free(struct XivEmoteRepository *repo);
+
+/**
+ * Whether the repository has an emote with the command, false if any argument is invalid.
+ *
+ * # Safety
+ * `repo` must be a valid repository and `command` a valid null terminated string.
+ */
+bool xiv_emote_has_emote(const struct XivEmoteRepository *repo, const char *command);
+
+/**
+ * Renders the log message of the emote with the command, in the language with the given code
+ * such as `en`. The untargeted message is rendered when `target` is null. On success, the
+ * message is written to `out` and must be freed with [xiv_emote_string_free].
+ *
+ * # Safety
+ * `repo` must be a valid repository, `command` and `language` valid null terminated strings,
+ * `origin` a valid character, `target` null or a valid character, and `out` valid for
+ * writes.
+ */
+enum XivEmoteStatus xiv_emote_render(const struct XivEmoteRepository *repo,
+                                     const char *command,
+                                     const char *language,
+                                     const struct XivEmoteCharacter *origin,
+                                     const struct XivEmoteCharacter *target,
+                                     char **out);
+
+/**
+ * Frees a string returned by this library, doing nothing if it is null.
+ *
+ * # Safety
+ * `s` must be null or a string from this library which has not already been freed.
+ */
+void xiv_emote_string_free(char *s);
+
+/**
+ * The message of the last error on the calling thread, or null if there has not been one.
+ * The string is owned by the library and is valid until the next failing call on the thread.
+ */
+const char *xiv_emote_last_error(void);
+
+#endif  /* XIV_EMOTE_PARSER_H */
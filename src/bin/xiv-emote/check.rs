@@ -0,0 +1,51 @@
+use std::{error::Error, process::ExitCode};
+
+use clap::Args;
+
+use crate::Source;
+
+#[derive(Debug, Args)]
+pub struct CheckArgs {
+    #[command(flatten)]
+    source: Source,
+    /// Number of example emotes to list for each construct
+    #[arg(long, default_value_t = 3)]
+    examples: usize,
+}
+
+/// Prints the failing log messages by language and construct, exiting with failure if there
+/// are any.
+pub fn run(args: CheckArgs) -> Result<ExitCode, Box<dyn Error>> {
+    let repo = args.source.load()?;
+    let report = repo.validate();
+    for (language, constructs) in report.by_construct() {
+        println!("{}:", language.code());
+        for (construct, failures) in constructs {
+            let mut names = Vec::new();
+            for failure in &failures {
+                if !names.contains(&failure.name.as_str()) {
+                    names.push(failure.name.as_str());
+                }
+            }
+            let mut examples = names[..names.len().min(args.examples)].join(", ");
+            if names.len() > args.examples {
+                examples.push_str(", ...");
+            }
+            println!("  {}: {} ({})", construct, failures.len(), examples);
+        }
+    }
+    let load_report = repo.load_report();
+    if !load_report.skipped.is_empty() {
+        println!("skipped {} emotes while loading", load_report.skipped.len());
+    }
+    println!(
+        "checked {} messages, {} failed",
+        report.checked,
+        report.failure_count()
+    );
+    Ok(if report.is_ok() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
@@ -0,0 +1,99 @@
+//! Command-line tool for working with emote log messages from a repository dump.
+
+use std::{error::Error, path::PathBuf, process::ExitCode};
+
+use clap::{Args, Parser, Subcommand};
+use xiv_emote_parser::repository::{Language, LogMessageRepository};
+
+mod check;
+mod render;
+mod repl;
+#[cfg(feature = "server")]
+mod serve;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "xiv-emote",
+    version,
+    about = "Works with FFXIV emote log messages"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Render the log message of an emote
+    Render(render::RenderArgs),
+    /// Check that every log message in the repository can be processed
+    Check(check::CheckArgs),
+    /// Interactively parse log messages and toggle their conditions
+    Repl(repl::ReplArgs),
+    /// Serve a json api for listing and rendering emotes
+    #[cfg(feature = "server")]
+    Serve(serve::ServeArgs),
+}
+
+/// Where to load the repository from.
+#[derive(Debug, Args)]
+#[group(required = true, multiple = false)]
+pub struct Source {
+    /// Repository json, a list of emotes as read by `LogMessageRepository::from_json`
+    #[arg(long)]
+    json: Option<PathBuf>,
+    /// Saved xivapi emote responses, one file per page
+    #[arg(long, num_args = 1..)]
+    dump: Vec<PathBuf>,
+}
+
+impl Source {
+    pub fn load(&self) -> Result<LogMessageRepository, Box<dyn Error>> {
+        Ok(match &self.json {
+            Some(json) => LogMessageRepository::from_json(&std::fs::read_to_string(json)?)?,
+            None => {
+                let pages = self
+                    .dump
+                    .iter()
+                    .map(std::fs::read_to_string)
+                    .collect::<Result<Vec<_>, _>>()?;
+                LogMessageRepository::from_xivapi_dump(&pages)?
+            }
+        })
+    }
+}
+
+pub fn parse_language(code: &str) -> Result<Language, String> {
+    Language::from_code(code).ok_or_else(|| {
+        let codes: Vec<_> = Language::ALL.iter().map(Language::code).collect();
+        format!("expected one of {}", codes.join(", "))
+    })
+}
+
+/// Prints the error and its sources to stderr.
+pub fn print_error(e: &dyn Error) {
+    eprintln!("error: {}", e);
+    let mut source = e.source();
+    while let Some(e) = source {
+        eprintln!("  caused by: {}", e);
+        source = e.source();
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let res = match cli.command {
+        Command::Render(args) => render::run(args),
+        Command::Check(args) => check::run(args),
+        Command::Repl(args) => repl::run(args),
+        #[cfg(feature = "server")]
+        Command::Serve(args) => serve::run(args),
+    };
+    match res {
+        Ok(code) => code,
+        Err(e) => {
+            print_error(e.as_ref());
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use std::{error::Error, process::ExitCode};
+
+use clap::{Args, ValueEnum};
+use xiv_emote_parser::{
+    log_message::{
+        condition::{Character, Gender, LogMessageAnswers},
+        process_log_message,
+    },
+    repository::{Language, LogMessageRepository},
+};
+
+use crate::{parse_language, Source};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum GenderArg {
+    Male,
+    Female,
+}
+
+impl From<GenderArg> for Gender {
+    fn from(gender: GenderArg) -> Gender {
+        match gender {
+            GenderArg::Male => Gender::Male,
+            GenderArg::Female => Gender::Female,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct RenderArgs {
+    #[command(flatten)]
+    source: Source,
+    /// The emote's command, ex. /surprised
+    command: String,
+    #[arg(short, long, default_value = "en", value_parser = parse_language)]
+    language: Language,
+    /// Name of the character using the emote
+    #[arg(long, default_value = "Origin")]
+    origin: String,
+    #[arg(long, value_enum, default_value_t = GenderArg::Female)]
+    origin_gender: GenderArg,
+    /// The character using the emote is not a player
+    #[arg(long)]
+    origin_npc: bool,
+    /// The character using the emote is you
+    #[arg(long)]
+    origin_self: bool,
+    /// Name of the emote's target, rendering the untargeted message if not given
+    #[arg(long)]
+    target: Option<String>,
+    #[arg(long, value_enum, default_value_t = GenderArg::Female)]
+    target_gender: GenderArg,
+    /// The emote's target is not a player
+    #[arg(long)]
+    target_npc: bool,
+    /// The emote's target is you
+    #[arg(long)]
+    target_self: bool,
+    /// Print the message for every combination of targeting, genders, player and self flags,
+    /// ignoring those given
+    #[arg(long)]
+    all_variants: bool,
+}
+
+struct Variant {
+    origin: Character,
+    /// the target, or none to render the untargeted message
+    target: Option<Character>,
+}
+
+impl Variant {
+    fn describe(character: &Character) -> String {
+        format!(
+            "{} {}{}",
+            character.gender.to_string().to_lowercase(),
+            if character.is_pc { "player" } else { "npc" },
+            if character.is_self { " (self)" } else { "" }
+        )
+    }
+
+    fn label(&self) -> String {
+        match &self.target {
+            Some(target) => format!(
+                "targeted, origin: {}, target: {}",
+                Self::describe(&self.origin),
+                Self::describe(target)
+            ),
+            None => format!("untargeted, origin: {}", Self::describe(&self.origin)),
+        }
+    }
+}
+
+impl RenderArgs {
+    fn variant(&self) -> Variant {
+        Variant {
+            origin: Character::new_from_string(
+                self.origin.clone(),
+                self.origin_gender.into(),
+                !self.origin_npc,
+                self.origin_self,
+            ),
+            target: self.target.as_ref().map(|target| {
+                Character::new_from_string(
+                    target.clone(),
+                    self.target_gender.into(),
+                    !self.target_npc,
+                    self.target_self,
+                )
+            }),
+        }
+    }
+
+    fn all_variants(&self) -> Vec<Variant> {
+        let target = self.target.clone().unwrap_or_else(|| "Target".to_string());
+        let mut variants = Vec::new();
+        for targeted in [true, false] {
+            // which of the characters is you, if either
+            let selves: &[(bool, bool)] = if targeted {
+                &[(false, false), (true, false), (false, true)]
+            } else {
+                &[(false, false), (true, false)]
+            };
+            let target_flags: &[(Gender, bool)] = if targeted {
+                &[
+                    (Gender::Female, true),
+                    (Gender::Male, true),
+                    (Gender::Female, false),
+                    (Gender::Male, false),
+                ]
+            } else {
+                &[(Gender::Female, true)]
+            };
+            for &(origin_self, target_self) in selves {
+                for origin_gender in [Gender::Female, Gender::Male] {
+                    for origin_pc in [true, false] {
+                        // only players can be you
+                        if origin_self && !origin_pc {
+                            continue;
+                        }
+                        for (target_gender, target_pc) in target_flags.iter().cloned() {
+                            if target_self && !target_pc {
+                                continue;
+                            }
+                            variants.push(Variant {
+                                origin: Character::new_from_string(
+                                    self.origin.clone(),
+                                    origin_gender.clone(),
+                                    origin_pc,
+                                    origin_self,
+                                ),
+                                target: targeted.then(|| {
+                                    Character::new_from_string(
+                                        target.clone(),
+                                        target_gender,
+                                        target_pc,
+                                        target_self,
+                                    )
+                                }),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        variants
+    }
+}
+
+fn render(
+    repo: &LogMessageRepository,
+    args: &RenderArgs,
+    variant: Variant,
+) -> Result<String, Box<dyn Error>> {
+    let (log_msg, answers) = match variant.target {
+        Some(target) => (
+            repo.targeted(&args.command, args.language)?,
+            LogMessageAnswers::new(variant.origin, target)?,
+        ),
+        None => (
+            repo.untargeted(&args.command, args.language)?,
+            LogMessageAnswers::untargeted(variant.origin),
+        ),
+    };
+    Ok(process_log_message(log_msg, &answers)?)
+}
+
+pub fn run(args: RenderArgs) -> Result<ExitCode, Box<dyn Error>> {
+    let repo = args.source.load()?;
+    if args.all_variants {
+        for variant in args.all_variants() {
+            let label = variant.label();
+            println!("[{}] {}", label, render(&repo, &args, variant)?);
+        }
+    } else {
+        println!("{}", render(&repo, &args, args.variant())?);
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,175 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    error::Error,
+    io::{self, BufRead, Write},
+    process::ExitCode,
+};
+
+use clap::Args;
+use xiv_emote_parser::log_message::{
+    ast::types::{ConditionText, ConditionTexts, Message, Text},
+    condition::{Condition, ConditionAnswer, DynamicText, DynamicTextAnswer},
+    parser::parse_message,
+};
+
+use crate::print_error;
+
+const HELP: &str = "\
+Paste a raw log message to parse it, then toggle its conditions to see the output change.
+  <message>       parse a new log message
+  <n> [<n>...]    toggle the numbered conditions
+  :ast            print the parsed ast again
+  :texts          print the condition texts again
+  :help           print this help
+  :quit           exit, as does end of input";
+
+#[derive(Debug, Args)]
+pub struct ReplArgs {
+    /// Name used for the origin of the message
+    #[arg(long, default_value = "Origin")]
+    origin: String,
+    /// Name used for the target of the message
+    #[arg(long, default_value = "Target")]
+    target: String,
+}
+
+/// Answers conditions with the values toggled by the user, which are kept across messages.
+struct Toggles {
+    values: HashMap<Condition, bool>,
+    origin: String,
+    target: String,
+}
+
+impl ConditionAnswer for Toggles {
+    fn as_bool(&self, cond: &Condition) -> bool {
+        self.values.get(cond).copied().unwrap_or_default()
+    }
+}
+
+impl DynamicTextAnswer for Toggles {
+    fn as_str(&self, text: &DynamicText) -> Cow<'static, str> {
+        match text {
+            DynamicText::NpcOriginName
+            | DynamicText::PlayerOriginNameEn
+            | DynamicText::PlayerOriginNameJp => Cow::Owned(self.origin.clone()),
+            DynamicText::NpcTargetName
+            | DynamicText::PlayerTargetNameEn
+            | DynamicText::PlayerTargetNameJp => Cow::Owned(self.target.clone()),
+        }
+    }
+}
+
+struct Parsed {
+    message: Message,
+    texts: ConditionTexts,
+    conditions: Vec<Condition>,
+}
+
+impl Parsed {
+    fn new(log_msg: &str) -> Result<Parsed, Box<dyn Error>> {
+        let message = parse_message(log_msg)?;
+        let texts = message.process_string()?;
+        let conditions = texts.conditions();
+        Ok(Parsed {
+            message,
+            texts,
+            conditions,
+        })
+    }
+
+    fn print_ast(&self) {
+        println!("ast:\n{:#?}", self.message);
+    }
+
+    fn print_texts(&self) {
+        println!("condition texts:");
+        for ConditionText { conds, text } in self.texts.clone().into_inner() {
+            let conds: Vec<_> = conds
+                .iter()
+                .map(|state| {
+                    let not = if state.is_true { "" } else { "!" };
+                    format!("{}{:?}", not, state.cond)
+                })
+                .collect();
+            let text = match text {
+                Text::Static(s) => format!("{:?}", s),
+                Text::Dynamic(d) => format!("{{{:?}}}", d),
+            };
+            println!("  [{}] {}", conds.join(", "), text);
+        }
+    }
+
+    fn print_output(&self, toggles: &Toggles) {
+        println!("conditions:");
+        for (i, cond) in self.conditions.iter().enumerate() {
+            println!("  {}. {:?} = {}", i + 1, cond, toggles.as_bool(cond));
+        }
+        let output: String = self
+            .texts
+            .map_texts(toggles, |text| match text {
+                Text::Static(s) => s.clone(),
+                Text::Dynamic(d) => toggles.as_str(d).into_owned(),
+            })
+            .collect();
+        println!("output: {}", output);
+    }
+}
+
+/// The condition numbers of a toggle command, or [None] if the line is not one.
+fn toggle_numbers(line: &str) -> Option<Vec<usize>> {
+    line.split_whitespace().map(|n| n.parse().ok()).collect()
+}
+
+pub fn run(args: ReplArgs) -> Result<ExitCode, Box<dyn Error>> {
+    let mut toggles = Toggles {
+        values: HashMap::new(),
+        origin: args.origin,
+        target: args.target,
+    };
+    let mut parsed: Option<Parsed> = None;
+    println!("{}", HELP);
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
+        let Some(line) = lines.next().transpose()? else {
+            println!();
+            break;
+        };
+        let line = line.trim();
+        match (line, &parsed) {
+            ("", _) => {}
+            (":quit" | ":q", _) => break,
+            (":help", _) => println!("{}", HELP),
+            (":ast", Some(parsed)) => parsed.print_ast(),
+            (":texts", Some(parsed)) => parsed.print_texts(),
+            (":ast" | ":texts", None) => println!("no log message parsed yet"),
+            _ => match (toggle_numbers(line), &parsed) {
+                (Some(numbers), Some(current)) => {
+                    for n in numbers {
+                        match n.checked_sub(1).and_then(|i| current.conditions.get(i)) {
+                            Some(cond) => {
+                                let value = toggles.values.entry(*cond).or_default();
+                                *value = !*value;
+                            }
+                            None => println!("no condition {}", n),
+                        }
+                    }
+                    current.print_output(&toggles);
+                }
+                _ => match Parsed::new(line) {
+                    Ok(new) => {
+                        new.print_ast();
+                        new.print_texts();
+                        new.print_output(&toggles);
+                        parsed = Some(new);
+                    }
+                    Err(e) => print_error(e.as_ref()),
+                },
+            },
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,169 @@
+//! A small json api over a repository, for services that can not use the library directly.
+//!
+//! - `GET /emotes` lists every emote as `{ "id", "name", "commands" }`, sorted by id
+//! - `GET /emotes/{command}` gives an emote's names and log messages by language code, where
+//!   the command is percent encoded and may leave out the leading slash
+//! - `POST /render` renders a log message, see [RenderRequest]
+//!
+//! Errors are returned as `{ "error": "..." }` with an appropriate status code. Request bodies
+//! larger than [MAX_BODY_SIZE] are refused with 413.
+
+use std::{error::Error, io::Read, process::ExitCode};
+
+use clap::Args;
+use percent_encoding::percent_decode_str;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Response, Server};
+use xiv_emote_parser::{
+    log_message::{
+        condition::{Character, LogMessageAnswers},
+        process_log_message,
+    },
+    repository::{EmoteView, Language, LogMessageRepository, LogMessageRepositoryError},
+};
+
+use crate::Source;
+
+/// The largest request body read, in bytes, which is far more than any render request needs.
+pub const MAX_BODY_SIZE: u64 = 64 * 1024;
+
+#[derive(Debug, Args)]
+pub struct ServeArgs {
+    #[command(flatten)]
+    source: Source,
+    /// Address to listen on, where port 0 picks any free port
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    address: String,
+}
+
+#[derive(Debug, Serialize)]
+struct EmoteSummary<'a> {
+    id: u32,
+    name: &'a str,
+    commands: &'a [String],
+}
+
+/// The body of `POST /render`, ex.
+/// `{ "command": "/surprised", "language": "en", "origin": { "name": "Alice", "gender": "female" } }`.
+///
+/// Characters are as serialized by [Character], so they are players unless `is_pc` is false,
+/// and not the viewer unless `is_self` is true. The untargeted message is rendered when there is no target.
+#[derive(Debug, Deserialize)]
+struct RenderRequest {
+    command: String,
+    #[serde(default = "default_language")]
+    language: String,
+    origin: Character,
+    #[serde(default)]
+    target: Option<Character>,
+}
+
+fn default_language() -> String {
+    Language::En.code().to_string()
+}
+
+/// A failed request, with the status code to respond with.
+struct ApiError(u16, String);
+
+impl From<LogMessageRepositoryError> for ApiError {
+    fn from(e: LogMessageRepositoryError) -> ApiError {
+        let status = match e {
+            LogMessageRepositoryError::NotFound | LogMessageRepositoryError::NoLogMessages => 404,
+            _ => 500,
+        };
+        ApiError(status, e.to_string())
+    }
+}
+
+type ApiResult = Result<Value, ApiError>;
+
+fn emotes(repo: &LogMessageRepository) -> ApiResult {
+    let mut emotes: Vec<_> = repo.emotes().collect();
+    emotes.sort_unstable_by_key(|data| data.id);
+    let summaries: Vec<_> = emotes
+        .into_iter()
+        .map(|data| EmoteSummary {
+            id: data.id,
+            name: &data.name,
+            commands: &data.commands,
+        })
+        .collect();
+    Ok(json!(summaries))
+}
+
+fn emote(repo: &LogMessageRepository, command: &str) -> ApiResult {
+    let command = percent_decode_str(command)
+        .decode_utf8()
+        .map_err(|e| ApiError(400, format!("Invalid command ({})", e)))?;
+    let data = repo.messages(&command)?;
+    Ok(json!(EmoteView::from(data.as_ref())))
+}
+
+fn render(repo: &LogMessageRepository, body: &str) -> ApiResult {
+    let request: RenderRequest = serde_json::from_str(body)
+        .map_err(|e| ApiError(400, format!("Invalid render request ({})", e)))?;
+    let language = Language::from_code(&request.language)
+        .ok_or_else(|| ApiError(400, format!("Unknown language ({})", request.language)))?;
+    let (log_msg, answers) = match request.target {
+        Some(target) => (
+            repo.targeted(&request.command, language)?,
+            LogMessageAnswers::new(request.origin, target)
+                .map_err(|e| ApiError(400, e.to_string()))?,
+        ),
+        None => (
+            repo.untargeted(&request.command, language)?,
+            LogMessageAnswers::untargeted(request.origin),
+        ),
+    };
+    let message = process_log_message(log_msg, &answers)
+        .map_err(|e| ApiError(500, format!("{} ({})", e, log_msg)))?;
+    Ok(json!({ "message": message }))
+}
+
+/// Routes a request to its handler, returning the status code and json body.
+fn handle(repo: &LogMessageRepository, method: &Method, url: &str, body: &str) -> (u16, Value) {
+    let path = url.split_once('?').map_or(url, |(path, _)| path);
+    let res = match (method, path) {
+        (Method::Get, "/emotes") => emotes(repo),
+        (Method::Get, _) if path.starts_with("/emotes/") => emote(repo, &path["/emotes/".len()..]),
+        (Method::Post, "/render") => render(repo, body),
+        (_, "/emotes" | "/render") => Err(ApiError(405, "Method not allowed".to_string())),
+        _ if path.starts_with("/emotes/") => Err(ApiError(405, "Method not allowed".to_string())),
+        _ => Err(ApiError(404, "Not found".to_string())),
+    };
+    match res {
+        Ok(value) => (200, value),
+        Err(ApiError(status, error)) => (status, json!({ "error": error })),
+    }
+}
+
+/// Serves requests one at a time until the process is stopped.
+pub fn run(args: ServeArgs) -> Result<ExitCode, Box<dyn Error>> {
+    let repo = args.source.load()?;
+    let server = Server::http(&args.address).map_err(|e| -> Box<dyn Error> { e })?;
+    println!("listening on http://{}", server.server_addr());
+    let content_type = Header::from_bytes("Content-Type", "application/json")
+        .expect("content type header is valid");
+    for mut request in server.incoming_requests() {
+        let mut body = String::new();
+        let read = request
+            .as_reader()
+            .take(MAX_BODY_SIZE + 1)
+            .read_to_string(&mut body);
+        let (status, value) = match read {
+            Ok(size) if size as u64 > MAX_BODY_SIZE => {
+                (413, json!({ "error": "Request body too large" }))
+            }
+            Ok(_) => handle(&repo, request.method(), request.url(), &body),
+            Err(e) => (400, json!({ "error": format!("Invalid body ({})", e) })),
+        };
+        let response = Response::from_string(value.to_string())
+            .with_status_code(status)
+            .with_header(content_type.clone());
+        if let Err(e) = request.respond(response) {
+            eprintln!("error: could not respond ({})", e);
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
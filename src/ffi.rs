@@ -0,0 +1,315 @@
+//! C ABI for loading a repository and rendering emotes from other languages, declared in
+//! `include/xiv_emote_parser.h`.
+//!
+//! Functions return an [XivEmoteStatus], writing their results through out pointers only on
+//! success. Strings are null terminated UTF-8, and anything allocated here must be released
+//! with the matching free function. The message of the last error on the calling thread is
+//! available from [xiv_emote_last_error]. Panics are caught before they reach the caller and
+//! returned as [XivEmoteStatus::Other].
+
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CStr, CString},
+    panic::{self, AssertUnwindSafe},
+    ptr,
+};
+
+use crate::{
+    log_message::{
+        condition::{Character, Gender, LogMessageAnswers},
+        process_log_message, EmoteTextError, EmoteTextErrorKind,
+    },
+    repository::{Language, LogMessageRepository, LogMessageRepositoryError},
+};
+
+pub const XIV_EMOTE_GENDER_MALE: u8 = 0;
+pub const XIV_EMOTE_GENDER_FEMALE: u8 = 1;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XivEmoteStatus {
+    Ok = 0,
+    /// a required pointer argument was null
+    NullArgument,
+    /// a string argument was not valid UTF-8
+    InvalidUtf8,
+    /// an unknown language code or gender
+    InvalidArgument,
+    /// the repository json could not be read
+    InvalidJson,
+    /// no emote has the command
+    NotFound,
+    /// the emote has no log messages in the language
+    NoLogMessages,
+    /// both characters are self but are not the same character
+    MultipleSelves,
+    /// see [EmoteTextError::ParseError]
+    ParseError,
+    /// see [EmoteTextError::AstError]
+    AstError,
+    /// see [EmoteTextError::MessageParseError]
+    MessageParseError,
+    /// see [EmoteTextError::ProcessError]
+    ProcessError,
+    /// see [EmoteTextError::DecodeError]
+    DecodeError,
+    /// the rendered message contained a null character
+    InteriorNul,
+    /// any other error, including a panic inside the library
+    Other,
+}
+
+impl From<&EmoteTextError> for XivEmoteStatus {
+    fn from(e: &EmoteTextError) -> XivEmoteStatus {
+        match e.kind() {
+            EmoteTextErrorKind::ParseError => XivEmoteStatus::ParseError,
+            EmoteTextErrorKind::AstError => XivEmoteStatus::AstError,
+            EmoteTextErrorKind::MessageParseError => XivEmoteStatus::MessageParseError,
+            EmoteTextErrorKind::ProcessError(_) => XivEmoteStatus::ProcessError,
+            EmoteTextErrorKind::DecodeError => XivEmoteStatus::DecodeError,
+        }
+    }
+}
+
+impl From<&LogMessageRepositoryError> for XivEmoteStatus {
+    fn from(e: &LogMessageRepositoryError) -> XivEmoteStatus {
+        match e {
+            LogMessageRepositoryError::NotFound => XivEmoteStatus::NotFound,
+            LogMessageRepositoryError::NoLogMessages => XivEmoteStatus::NoLogMessages,
+            LogMessageRepositoryError::InvalidJsonInput(_) => XivEmoteStatus::InvalidJson,
+            #[allow(unreachable_patterns)]
+            _ => XivEmoteStatus::Other,
+        }
+    }
+}
+
+/// A character of a message, where the name is a null terminated UTF-8 string and the gender
+/// is one of the `XIV_EMOTE_GENDER` constants.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct XivEmoteCharacter {
+    pub name: *const c_char,
+    pub gender: u8,
+    pub is_pc: bool,
+    pub is_self: bool,
+}
+
+/// Opaque handle to a repository.
+pub struct XivEmoteRepository(LogMessageRepository);
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Records the error message for [xiv_emote_last_error], returning its status.
+fn fail(status: XivEmoteStatus, message: impl ToString) -> XivEmoteStatus {
+    let message = CString::new(message.to_string().replace('\0', " "))
+        .expect("null characters were replaced");
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+    status
+}
+
+/// Runs the body of an exported function, returning `on_panic` if it panics, since unwinding
+/// into the caller is undefined behavior. The panic message is recorded as the last error.
+fn catch_panic<T>(on_panic: T, body: impl FnOnce() -> T) -> T {
+    // nothing the body touched is used again after a panic, other than the last error
+    panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_default();
+        fail(XivEmoteStatus::Other, format!("Panicked ({})", message));
+        on_panic
+    })
+}
+
+/// Reads a required string argument.
+///
+/// # Safety
+/// `s` must be null or a valid null terminated string.
+unsafe fn read_str<'a>(s: *const c_char) -> Result<&'a str, XivEmoteStatus> {
+    if s.is_null() {
+        return Err(fail(XivEmoteStatus::NullArgument, "Null string argument"));
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map_err(|e| fail(XivEmoteStatus::InvalidUtf8, e))
+}
+
+/// # Safety
+/// `character.name` must be null or a valid null terminated string.
+unsafe fn read_character(character: &XivEmoteCharacter) -> Result<Character, XivEmoteStatus> {
+    let name = read_str(character.name)?;
+    let gender = match character.gender {
+        XIV_EMOTE_GENDER_MALE => Gender::Male,
+        XIV_EMOTE_GENDER_FEMALE => Gender::Female,
+        gender => {
+            return Err(fail(
+                XivEmoteStatus::InvalidArgument,
+                format!("Unknown gender ({})", gender),
+            ))
+        }
+    };
+    Ok(Character::new_from_string(
+        name.to_string(),
+        gender,
+        character.is_pc,
+        character.is_self,
+    ))
+}
+
+macro_rules! try_status {
+    ($e:expr) => {
+        match $e {
+            Ok(value) => value,
+            Err(status) => return status,
+        }
+    };
+}
+
+/// Loads a repository from json, see [LogMessageRepository::from_json]. On success, the
+/// repository is written to `out` and must be freed with [xiv_emote_repository_free].
+///
+/// # Safety
+/// `json` must be a valid null terminated string and `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn xiv_emote_repository_from_json(
+    json: *const c_char,
+    out: *mut *mut XivEmoteRepository,
+) -> XivEmoteStatus {
+    catch_panic(XivEmoteStatus::Other, || {
+        if out.is_null() {
+            return fail(XivEmoteStatus::NullArgument, "Null out argument");
+        }
+        let json = try_status!(read_str(json));
+        match LogMessageRepository::from_json(json) {
+            Ok(repo) => {
+                *out = Box::into_raw(Box::new(XivEmoteRepository(repo)));
+                XivEmoteStatus::Ok
+            }
+            Err(e) => fail((&e).into(), e),
+        }
+    })
+}
+
+/// Frees a repository, doing nothing if it is null.
+///
+/// # Safety
+/// `repo` must be null or a repository from [xiv_emote_repository_from_json] which has not
+/// already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn xiv_emote_repository_free(repo: *mut XivEmoteRepository) {
+    catch_panic((), || {
+        if !repo.is_null() {
+            drop(Box::from_raw(repo));
+        }
+    })
+}
+
+/// Whether the repository has an emote with the command, false if any argument is invalid.
+///
+/// # Safety
+/// `repo` must be a valid repository and `command` a valid null terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn xiv_emote_has_emote(
+    repo: *const XivEmoteRepository,
+    command: *const c_char,
+) -> bool {
+    catch_panic(false, || match (repo.as_ref(), read_str(command)) {
+        (Some(repo), Ok(command)) => repo.0.contains_emote(command),
+        _ => false,
+    })
+}
+
+/// Renders the log message of the emote with the command, in the language with the given code
+/// such as `en`. The untargeted message is rendered when `target` is null. On success, the
+/// message is written to `out` and must be freed with [xiv_emote_string_free].
+///
+/// # Safety
+/// `repo` must be a valid repository, `command` and `language` valid null terminated strings,
+/// `origin` a valid character, `target` null or a valid character, and `out` valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn xiv_emote_render(
+    repo: *const XivEmoteRepository,
+    command: *const c_char,
+    language: *const c_char,
+    origin: *const XivEmoteCharacter,
+    target: *const XivEmoteCharacter,
+    out: *mut *mut c_char,
+) -> XivEmoteStatus {
+    catch_panic(XivEmoteStatus::Other, || {
+        let (Some(repo), Some(origin)) = (repo.as_ref(), origin.as_ref()) else {
+            return fail(XivEmoteStatus::NullArgument, "Null repository or origin");
+        };
+        if out.is_null() {
+            return fail(XivEmoteStatus::NullArgument, "Null out argument");
+        }
+        let command = try_status!(read_str(command));
+        let code = try_status!(read_str(language));
+        let Some(language) = Language::from_code(code) else {
+            return fail(
+                XivEmoteStatus::InvalidArgument,
+                format!("Unknown language ({})", code),
+            );
+        };
+        let origin = try_status!(read_character(origin));
+        let target = match target.as_ref() {
+            Some(target) => Some(try_status!(read_character(target))),
+            None => None,
+        };
+        let log_msg = match target {
+            Some(_) => repo.0.targeted(command, language),
+            None => repo.0.untargeted(command, language),
+        };
+        let log_msg = match log_msg {
+            Ok(log_msg) => log_msg,
+            Err(e) => return fail((&e).into(), e),
+        };
+        let answers = match target {
+            Some(target) => match LogMessageAnswers::new(origin, target) {
+                Ok(answers) => answers,
+                Err(e) => return fail(XivEmoteStatus::MultipleSelves, e),
+            },
+            None => LogMessageAnswers::untargeted(origin),
+        };
+        let message = match process_log_message(log_msg, &answers) {
+            Ok(message) => message,
+            Err(e) => return fail((&e).into(), e),
+        };
+        match CString::new(message) {
+            Ok(message) => {
+                *out = message.into_raw();
+                XivEmoteStatus::Ok
+            }
+            Err(e) => fail(XivEmoteStatus::InteriorNul, e),
+        }
+    })
+}
+
+/// Frees a string returned by this library, doing nothing if it is null.
+///
+/// # Safety
+/// `s` must be null or a string from this library which has not already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn xiv_emote_string_free(s: *mut c_char) {
+    catch_panic((), || {
+        if !s.is_null() {
+            drop(CString::from_raw(s));
+        }
+    })
+}
+
+/// The message of the last error on the calling thread, or null if there has not been one.
+/// The string is owned by the library and is valid until the next failing call on the thread.
+#[no_mangle]
+pub extern "C" fn xiv_emote_last_error() -> *const c_char {
+    catch_panic(ptr::null(), || {
+        LAST_ERROR.with(|last| {
+            last.borrow()
+                .as_ref()
+                .map_or(ptr::null(), |message| message.as_ptr())
+        })
+    })
+}
@@ -1,5 +1,23 @@
 //! Processes the emote log message format from Final Fantasy XIV Online
 //! and generates text output given information about related players.
 
+// pest's error type is large, and is returned throughout parsing
+#![allow(clippy::result_large_err)]
+
+#[cfg(all(
+    target_arch = "wasm32",
+    any(feature = "xivapi", feature = "xivapi-async")
+))]
+compile_error!("the xivapi features make network requests, which are not supported on wasm");
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
 pub mod log_message;
+#[cfg(feature = "python")]
+pub mod python;
 pub mod repository;
+pub mod sestring;
+#[cfg(feature = "sqpack")]
+pub mod sqpack;
+#[cfg(feature = "wasm")]
+pub mod wasm;
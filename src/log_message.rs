@@ -2,13 +2,18 @@ use thiserror::Error;
 
 use self::ast::types::EmoteTextProcessError;
 
-mod ast;
+pub mod ast;
+pub mod description;
 pub mod parser;
 mod types;
 
 pub use self::ast::condition;
 pub use self::ast::condition::LogMessageAnswers;
+pub use self::ast::types::EmoteTextProcessErrorKind;
+pub use self::ast::{PlayerWorlds, SeStringDecodeError, SeStringEncodeError};
 pub use self::parser::process_log_message;
+pub use self::parser::process_log_message_to_sestring;
+pub use self::parser::process_sestring_log_message;
 pub use self::parser::EmoteTextResult;
 use self::parser::Rule;
 
@@ -22,4 +27,28 @@ pub enum EmoteTextError {
     MessageParseError,
     #[error("Error while processing log message ast")]
     ProcessError(#[from] EmoteTextProcessError),
+    #[error("Could not decode SeString to intermediate ast")]
+    DecodeError(#[from] SeStringDecodeError),
+}
+
+/// The kind of an [EmoteTextError], without its details, for grouping errors together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum EmoteTextErrorKind {
+    ParseError,
+    AstError,
+    MessageParseError,
+    ProcessError(EmoteTextProcessErrorKind),
+    DecodeError,
+}
+
+impl EmoteTextError {
+    pub fn kind(&self) -> EmoteTextErrorKind {
+        match self {
+            EmoteTextError::ParseError(_) => EmoteTextErrorKind::ParseError,
+            EmoteTextError::AstError(_) => EmoteTextErrorKind::AstError,
+            EmoteTextError::MessageParseError => EmoteTextErrorKind::MessageParseError,
+            EmoteTextError::ProcessError(e) => EmoteTextErrorKind::ProcessError(e.into()),
+            EmoteTextError::DecodeError(_) => EmoteTextErrorKind::DecodeError,
+        }
+    }
 }
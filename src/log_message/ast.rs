@@ -2,5 +2,11 @@
 
 pub mod condition;
 mod condition_texts;
+mod decoder;
+mod display;
+mod encoder;
 mod parser;
 pub mod types;
+
+pub use self::decoder::SeStringDecodeError;
+pub use self::encoder::{PlayerWorlds, SeStringEncodeError};
@@ -6,13 +6,20 @@ use std::borrow::Cow;
 
 use thiserror::Error;
 
+#[cfg(feature = "json")]
+use serde_derive::{Deserialize, Serialize};
+
 pub use crate::log_message::types::Gender;
 
 use super::types::{FuncName, Function, IfParam, Obj, Param, Tag, TagName};
 
 /// Abstraction of conditions provided by functions and tags in log messages.
 /// Should only appear as the condition for an if-else.
-#[derive(Debug, Clone, Copy)]
+///
+/// With the `json` feature, serialized as its snake case name, ex. `"is_self_origin"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "snake_case"))]
 pub enum Condition {
     /// if the current player character is the origin of the message
     /// Equal(ObjectParameter(1),ObjectParameter(2))
@@ -36,7 +43,11 @@ pub enum Condition {
 
 /// Abstraction of text with value depending on contextual player data.
 /// Should only appear as the then portion of an if-else or otherwise as text.
+///
+/// With the `json` feature, serialized as its snake case name, ex. `"player_origin_name_en"`.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "snake_case"))]
 pub enum DynamicText {
     /// the name of the origin of the message when not a player
     /// ObjectParameter(2)
@@ -50,10 +61,10 @@ pub enum DynamicText {
     /// the EN name of the target of the message
     /// <SheetEn(ObjStr,2,PlayerParameter(8),1,1)/>
     PlayerTargetNameEn,
-    /// the JP name of the origin of the message
+    /// the JP name of the origin of the message, also used by the chs and ko clients
     /// <Sheet(ObjStr,PlayerParameter(7),0)/>
     PlayerOriginNameJp,
-    /// the JP name of the target of the message
+    /// the JP name of the target of the message, also used by the chs and ko clients
     /// <Sheet(ObjStr,PlayerParameter(8),0)/>
     PlayerTargetNameJp,
 }
@@ -68,14 +79,28 @@ pub trait DynamicTextAnswer {
 
 pub trait Answers: ConditionAnswer + DynamicTextAnswer {}
 
+/// A character of a message.
+///
+/// With the `json` feature, serialized as
+/// `{ "name": "Alice", "gender": "female", "is_pc": true, "is_self": false }`, where
+/// `is_pc` defaults to true and `is_self` to false when deserializing. The javascript names
+/// `isPc` and `isSelf` are also accepted, as passed to the wasm bindings.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
 pub struct Character {
     pub name: Cow<'static, str>,
     pub gender: Gender,
+    #[cfg_attr(feature = "json", serde(default = "default_is_pc", alias = "isPc"))]
     pub is_pc: bool,
+    #[cfg_attr(feature = "json", serde(default, alias = "isSelf"))]
     pub is_self: bool,
 }
 
+#[cfg(feature = "json")]
+fn default_is_pc() -> bool {
+    true
+}
+
 impl Character {
     pub const fn new(name: &'static str, gender: Gender, is_pc: bool, is_self: bool) -> Character {
         Character {
@@ -126,6 +151,15 @@ impl LogMessageAnswers {
         }
     }
 
+    /// Answers for an untargeted log message, which never refers to a target, so an unnamed
+    /// character that is not the viewer stands in for it.
+    pub fn untargeted(origin_character: Character) -> LogMessageAnswers {
+        LogMessageAnswers {
+            origin_character,
+            target_character: Character::new("", Gender::Female, false, false),
+        }
+    }
+
     pub fn origin_character(&self) -> &Character {
         &self.origin_character
     }
@@ -172,10 +206,27 @@ pub enum Origin {
     Tag(Tag),
 }
 
+/// Formats as the macro text of the function or tag.
+impl std::fmt::Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Origin::Function(fun) => fun.fmt(f),
+            Origin::Tag(tag) => tag.fmt(f),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Error)]
 #[error("Unknown condition ({0:?})")]
 pub struct ConditionError(Origin);
 
+impl ConditionError {
+    /// The function or tag which is not a known [Condition].
+    pub fn origin(&self) -> &Origin {
+        &self.0
+    }
+}
+
 // in TryFrom impls below, Err only bindings provided for clarity
 
 impl TryFrom<&Function> for Condition {
@@ -258,6 +309,13 @@ impl TryFrom<&IfParam> for Condition {
 #[error("Unknown dynamic text ({0:?})")]
 pub struct DynamicTextError(Origin);
 
+impl DynamicTextError {
+    /// The function or tag which is not a known [DynamicText].
+    pub fn origin(&self) -> &Origin {
+        &self.0
+    }
+}
+
 impl TryFrom<Function> for DynamicText {
     type Error = DynamicTextError;
 
@@ -1,11 +1,17 @@
 use tracing::*;
 
 use super::{
-    condition::ConditionAnswer,
+    condition::{Condition, ConditionAnswer},
     types::{ConditionState, ConditionText, Text},
 };
 
+#[cfg(feature = "json")]
+use serde_derive::{Deserialize, Serialize};
+
+/// With the `json` feature, serialized as a list of [ConditionText]s.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json", serde(transparent))]
 pub struct ConditionTexts(Vec<ConditionText>);
 
 impl ConditionTexts {
@@ -19,7 +25,7 @@ impl ConditionTexts {
         &'a self,
         cond_answer: &'a C,
         text_handler: F,
-    ) -> impl Iterator<Item = R> + '_
+    ) -> impl Iterator<Item = R> + 'a
     where
         F: Fn(&Text) -> Option<R> + 'a,
         C: ConditionAnswer,
@@ -46,7 +52,7 @@ impl ConditionTexts {
         self,
         cond_answer: &'a C,
         text_handler: F,
-    ) -> impl Iterator<Item = R> + '_
+    ) -> impl Iterator<Item = R> + 'a
     where
         F: Fn(Text) -> Option<R> + 'a,
         C: ConditionAnswer,
@@ -72,7 +78,7 @@ impl ConditionTexts {
         &'a self,
         cond_answer: &'a C,
         mut text_handler: F,
-    ) -> impl Iterator<Item = R> + '_
+    ) -> impl Iterator<Item = R> + 'a
     where
         F: FnMut(&Text) -> Option<R> + 'a,
         C: ConditionAnswer,
@@ -99,7 +105,7 @@ impl ConditionTexts {
         self,
         cond_answer: &'a C,
         mut text_handler: F,
-    ) -> impl Iterator<Item = R> + '_
+    ) -> impl Iterator<Item = R> + 'a
     where
         F: FnMut(Text) -> Option<R> + 'a,
         C: ConditionAnswer,
@@ -125,7 +131,7 @@ impl ConditionTexts {
         &'a self,
         cond_answer: &'a C,
         text_handler: F,
-    ) -> impl Iterator<Item = R> + '_
+    ) -> impl Iterator<Item = R> + 'a
     where
         F: Fn(&Text) -> R + 'a,
         C: ConditionAnswer,
@@ -151,7 +157,7 @@ impl ConditionTexts {
         self,
         cond_answer: &'a C,
         text_handler: F,
-    ) -> impl Iterator<Item = R> + '_
+    ) -> impl Iterator<Item = R> + 'a
     where
         F: Fn(Text) -> R + 'a,
         C: ConditionAnswer,
@@ -177,7 +183,7 @@ impl ConditionTexts {
         &'a self,
         cond_answer: &'a C,
         mut text_handler: F,
-    ) -> impl Iterator<Item = R> + '_
+    ) -> impl Iterator<Item = R> + 'a
     where
         F: FnMut(&Text) -> R + 'a,
         C: ConditionAnswer,
@@ -203,7 +209,7 @@ impl ConditionTexts {
         self,
         cond_answer: &'a C,
         mut text_handler: F,
-    ) -> impl Iterator<Item = R> + '_
+    ) -> impl Iterator<Item = R> + 'a
     where
         F: FnMut(Text) -> R + 'a,
         C: ConditionAnswer,
@@ -224,7 +230,7 @@ impl ConditionTexts {
     }
 
     /// Executes text_handler for each [Text] value of contained [ConditionText]s whose condition resolves to true
-    pub fn for_each_texts<'a, F, C>(&'a self, cond_answer: &'a C, mut text_handler: F)
+    pub fn for_each_texts<F, C>(&self, cond_answer: &C, mut text_handler: F)
     where
         F: FnMut(&Text),
         C: ConditionAnswer,
@@ -245,7 +251,7 @@ impl ConditionTexts {
 
     /// Executes text_handler for each [Text] value of contained [ConditionText]s whose condition resolves to true,
     /// consuming the [ConditionTexts]
-    pub fn into_for_each_texts<'a, F, C>(self, cond_answer: &'a C, mut text_handler: F)
+    pub fn into_for_each_texts<F, C>(self, cond_answer: &C, mut text_handler: F)
     where
         F: FnMut(Text),
         C: ConditionAnswer,
@@ -264,6 +270,17 @@ impl ConditionTexts {
         });
     }
 
+    /// The distinct conditions that the texts depend on, in the order they first appear.
+    pub fn conditions(&self) -> Vec<Condition> {
+        let mut conditions = Vec::new();
+        for ConditionState { cond, .. } in self.0.iter().flat_map(|ctxt| &ctxt.conds) {
+            if !conditions.contains(cond) {
+                conditions.push(*cond);
+            }
+        }
+        conditions
+    }
+
     pub fn into_inner(self) -> Vec<ConditionText> {
         self.0
     }
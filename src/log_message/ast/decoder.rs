@@ -0,0 +1,181 @@
+//! Conversion of decoded SeStrings to the intermediate ast, without going through the macro
+//! text form.
+
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use super::types::*;
+use crate::sestring::{code, expr, Expression, Macro, SeString, SeStringError, SeStringPart};
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum SeStringDecodeError {
+    #[error("Invalid SeString")]
+    SeString(#[from] SeStringError),
+    #[error("Unsupported macro in log message ({0:#04x})")]
+    UnsupportedMacro(u8),
+    #[error("Unsupported expression in log message ({0:?})")]
+    UnsupportedExpression(Expression),
+}
+
+type Result<T> = std::result::Result<T, SeStringDecodeError>;
+
+impl Message {
+    /// Decodes the raw bytes of an SeString log message, as found in the game's data files.
+    pub fn decode(bytes: &[u8]) -> Result<Message> {
+        Message::try_from(&SeString::decode(bytes)?)
+    }
+}
+
+impl TryFrom<&SeString> for Message {
+    type Error = SeStringDecodeError;
+
+    fn try_from(string: &SeString) -> Result<Message> {
+        string
+            .0
+            .iter()
+            .map(|part| match part {
+                SeStringPart::Text(text) => Ok(MessagePart::Text(text.clone())),
+                SeStringPart::Macro(m) => element(m).map(MessagePart::Element),
+            })
+            .collect::<Result<_>>()
+            .map(Message)
+    }
+}
+
+fn unsupported(expression: &Expression) -> SeStringDecodeError {
+    SeStringDecodeError::UnsupportedExpression(expression.clone())
+}
+
+fn element(m: &Macro) -> Result<Element> {
+    let tag_name = match m.code {
+        code::IF => {
+            return match &m.args[..] {
+                [cond, then, otherwise] => Ok(Element::IfElse(Box::new(IfElse {
+                    if_cond: if_param(cond)?,
+                    if_then: if_else_then(then)?,
+                    else_then: if_else_then(otherwise)?,
+                }))),
+                _ => Err(SeStringDecodeError::UnsupportedMacro(m.code)),
+            };
+        }
+        code::SWITCH => {
+            return match &m.args[..] {
+                [cond, cases @ .., last] if !cases.is_empty() => switch(cond, cases, last),
+                _ => Err(SeStringDecodeError::UnsupportedMacro(m.code)),
+            };
+        }
+        code::CLICKABLE => TagName::Clickable,
+        code::SHEET => TagName::Sheet,
+        code::SHEET_EN => TagName::SheetEn,
+        code => return Err(SeStringDecodeError::UnsupportedMacro(code)),
+    };
+    let params = m.args.iter().map(param).collect::<Result<_>>()?;
+    Ok(Element::Tag(
+        Tag {
+            name: tag_name,
+            params,
+        },
+        None,
+    ))
+}
+
+/// Switches have no counterpart in the macro text form, so they are converted to nested ifs
+/// comparing the switched value against each case number, with the last case as the final
+/// else.
+fn switch(cond: &Expression, cases: &[Expression], last: &Expression) -> Result<Element> {
+    let mut else_then = if_else_then(last)?;
+    for (i, case) in cases.iter().enumerate().rev() {
+        let if_else = IfElse {
+            if_cond: IfParam::Function(Function {
+                name: FuncName::Equal,
+                params: vec![param(cond)?, Param::Num(i as u32 + 1)],
+            }),
+            if_then: if_else_then(case)?,
+            else_then,
+        };
+        else_then = vec![IfElseThen::Element(Element::IfElse(Box::new(if_else)))];
+    }
+    match else_then.pop() {
+        Some(IfElseThen::Element(e)) => Ok(e),
+        _ => unreachable!("switches have at least one case before the last"),
+    }
+}
+
+fn function(expression: &Expression) -> Option<Result<Function>> {
+    Some(match expression {
+        Expression::Binary(expr::EQUAL, left, right) => param(left).and_then(|left| {
+            Ok(Function {
+                name: FuncName::Equal,
+                params: vec![left, param(right)?],
+            })
+        }),
+        Expression::Parameter(code @ (expr::OBJECT_PARAMETER | expr::PLAYER_PARAMETER), index) => {
+            let name = match *code {
+                expr::OBJECT_PARAMETER => FuncName::ObjectParameter,
+                _ => FuncName::PlayerParameter,
+            };
+            param(index).map(|index| Function {
+                name,
+                params: vec![index],
+            })
+        }
+        _ => return None,
+    })
+}
+
+/// The single macro of a string expression, which is how tags are passed as arguments.
+fn single_macro(string: &SeString) -> Option<&Macro> {
+    match &string.0[..] {
+        [SeStringPart::Macro(m)] => Some(m),
+        _ => None,
+    }
+}
+
+fn param(expression: &Expression) -> Result<Param> {
+    if let Some(function) = function(expression) {
+        return function.map(Param::Function);
+    }
+    match expression {
+        Expression::Integer(n) => Ok(Param::Num(*n)),
+        Expression::String(string) => match &string.0[..] {
+            [SeStringPart::Text(text)] => Obj::from_str(text)
+                .map(Param::Obj)
+                .map_err(|_| unsupported(expression)),
+            [SeStringPart::Macro(m)] => element(m).map(Param::Element),
+            _ => Err(unsupported(expression)),
+        },
+        _ => Err(unsupported(expression)),
+    }
+}
+
+fn if_param(expression: &Expression) -> Result<IfParam> {
+    if let Some(function) = function(expression) {
+        return function.map(IfParam::Function);
+    }
+    let tag = match expression {
+        Expression::String(string) => single_macro(string).map(element).transpose()?,
+        _ => None,
+    };
+    match tag {
+        Some(Element::Tag(tag, _)) => Ok(IfParam::Tag(tag)),
+        _ => Err(unsupported(expression)),
+    }
+}
+
+fn if_else_then(expression: &Expression) -> Result<Vec<IfElseThen>> {
+    if let Some(function) = function(expression) {
+        return Ok(vec![IfElseThen::Function(function?)]);
+    }
+    match expression {
+        Expression::String(string) => string
+            .0
+            .iter()
+            .map(|part| match part {
+                SeStringPart::Text(text) => Ok(IfElseThen::Text(text.clone())),
+                SeStringPart::Macro(m) => element(m).map(IfElseThen::Element),
+            })
+            .collect(),
+        _ => Err(unsupported(expression)),
+    }
+}
@@ -0,0 +1,104 @@
+//! Formatting of the intermediate ast back to the macro text form it was parsed from.
+
+use std::fmt::{self, Display, Formatter, Write};
+
+use super::types::*;
+
+fn write_params(f: &mut Formatter<'_>, params: &[Param]) -> fmt::Result {
+    if params.is_empty() {
+        return Ok(());
+    }
+    f.write_char('(')?;
+    for (i, param) in params.iter().enumerate() {
+        if i > 0 {
+            f.write_char(',')?;
+        }
+        param.fmt(f)?;
+    }
+    f.write_char(')')
+}
+
+impl Display for Message {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|part| part.fmt(f))
+    }
+}
+
+impl Display for MessagePart {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            MessagePart::Element(e) => e.fmt(f),
+            MessagePart::Text(text) => f.write_str(text),
+        }
+    }
+}
+
+impl Display for Element {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Element::IfElse(if_else) => if_else.fmt(f),
+            Element::Tag(tag, None) => tag.fmt(f),
+            Element::Tag(tag, Some(text)) => {
+                write!(f, "<{:?}", tag.name)?;
+                write_params(f, &tag.params)?;
+                write!(f, ">{}</{:?}>", text, tag.name)
+            }
+        }
+    }
+}
+
+impl Display for IfElse {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "<If({})>", self.if_cond)?;
+        self.if_then.iter().try_for_each(|part| part.fmt(f))?;
+        f.write_str("<Else/>")?;
+        self.else_then.iter().try_for_each(|part| part.fmt(f))?;
+        f.write_str("</If>")
+    }
+}
+
+impl Display for IfElseThen {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            IfElseThen::Function(fun) => fun.fmt(f),
+            IfElseThen::Element(e) => e.fmt(f),
+            IfElseThen::Text(text) => f.write_str(text),
+        }
+    }
+}
+
+impl Display for IfParam {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            IfParam::Function(fun) => fun.fmt(f),
+            IfParam::Tag(tag) => tag.fmt(f),
+        }
+    }
+}
+
+/// Formats as an auto closing tag, ex. `<Sheet(ObjStr,PlayerParameter(7),0)/>`.
+impl Display for Tag {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "<{:?}", self.name)?;
+        write_params(f, &self.params)?;
+        f.write_str("/>")
+    }
+}
+
+impl Display for Function {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.name)?;
+        write_params(f, &self.params)
+    }
+}
+
+impl Display for Param {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Param::Element(e) => e.fmt(f),
+            Param::Function(fun) => fun.fmt(f),
+            Param::Num(n) => n.fmt(f),
+            Param::Obj(obj) => f.write_str(obj.as_ref()),
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! Conversion of the intermediate ast and rendered messages to SeStrings, the inverse of
+//! [decoder](super::decoder).
+
+use thiserror::Error;
+
+use super::{
+    condition::{Answers, DynamicText},
+    types::*,
+};
+use crate::sestring::{code, expr, Expression, Macro, SeString, SeStringPart};
+
+#[derive(Debug, Clone, Error, PartialEq)]
+pub enum SeStringEncodeError {
+    #[error("Function can only be encoded as an entire if-else branch ({0:?})")]
+    FunctionInText(Function),
+    #[error("Invalid combination of function and parameters ({0:?})")]
+    InvalidFunc(Function),
+    #[error("Tag with text can not be encoded ({0:?})")]
+    TagWithText(Tag),
+}
+
+type Result<T> = std::result::Result<T, SeStringEncodeError>;
+
+impl Message {
+    /// Encodes the message to the raw bytes of an SeString, as found in the game's data files.
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        SeString::try_from(self).map(|string| string.encode())
+    }
+}
+
+impl TryFrom<&Message> for SeString {
+    type Error = SeStringEncodeError;
+
+    fn try_from(message: &Message) -> Result<SeString> {
+        message
+            .0
+            .iter()
+            .map(|part| match part {
+                MessagePart::Text(text) => Ok(SeStringPart::Text(text.clone())),
+                MessagePart::Element(e) => element(e).map(SeStringPart::Macro),
+            })
+            .collect::<Result<_>>()
+            .map(SeString)
+    }
+}
+
+fn element(element: &Element) -> Result<Macro> {
+    match element {
+        Element::IfElse(if_else) => Ok(Macro {
+            code: code::IF,
+            args: vec![
+                match &if_else.if_cond {
+                    IfParam::Function(f) => function(f)?,
+                    IfParam::Tag(t) => tag_string(t)?,
+                },
+                if_else_then(&if_else.if_then)?,
+                if_else_then(&if_else.else_then)?,
+            ],
+        }),
+        Element::Tag(t, None) => tag(t),
+        Element::Tag(t, Some(_)) => Err(SeStringEncodeError::TagWithText(t.clone())),
+    }
+}
+
+fn tag(tag: &Tag) -> Result<Macro> {
+    Ok(Macro {
+        code: match tag.name {
+            TagName::Clickable => code::CLICKABLE,
+            TagName::Sheet => code::SHEET,
+            TagName::SheetEn => code::SHEET_EN,
+        },
+        args: tag.params.iter().map(param).collect::<Result<_>>()?,
+    })
+}
+
+/// Tags are passed as arguments by wrapping them in a string expression.
+fn tag_string(t: &Tag) -> Result<Expression> {
+    Ok(Expression::String(SeString(vec![SeStringPart::Macro(
+        tag(t)?,
+    )])))
+}
+
+fn function(fun: &Function) -> Result<Expression> {
+    let parameter = |code| match &fun.params[..] {
+        [index] => Ok(Expression::Parameter(code, Box::new(param(index)?))),
+        _ => Err(SeStringEncodeError::InvalidFunc(fun.clone())),
+    };
+    match fun.name {
+        FuncName::Equal => match &fun.params[..] {
+            [left, right] => Ok(Expression::Binary(
+                expr::EQUAL,
+                Box::new(param(left)?),
+                Box::new(param(right)?),
+            )),
+            _ => Err(SeStringEncodeError::InvalidFunc(fun.clone())),
+        },
+        FuncName::ObjectParameter => parameter(expr::OBJECT_PARAMETER),
+        FuncName::PlayerParameter => parameter(expr::PLAYER_PARAMETER),
+    }
+}
+
+fn param(param: &Param) -> Result<Expression> {
+    match param {
+        Param::Element(e) => Ok(Expression::String(SeString(vec![SeStringPart::Macro(
+            element(e)?,
+        )]))),
+        Param::Function(f) => function(f),
+        Param::Num(n) => Ok(Expression::Integer(*n)),
+        Param::Obj(obj) => Ok(Expression::String(SeString(vec![SeStringPart::Text(
+            obj.as_ref().to_string(),
+        )]))),
+    }
+}
+
+fn if_else_then(parts: &[IfElseThen]) -> Result<Expression> {
+    if let [IfElseThen::Function(f)] = parts {
+        return function(f);
+    }
+    parts
+        .iter()
+        .map(|part| match part {
+            IfElseThen::Function(f) => Err(SeStringEncodeError::FunctionInText(f.clone())),
+            IfElseThen::Element(e) => element(e).map(SeStringPart::Macro),
+            IfElseThen::Text(text) => Ok(SeStringPart::Text(text.clone())),
+        })
+        .collect::<Result<_>>()
+        .map(|parts| Expression::String(SeString(parts)))
+}
+
+/// The home worlds of the characters of a message, as rows of the World sheet, which are used
+/// to link player names when rendering to an SeString.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PlayerWorlds {
+    pub origin: u32,
+    pub target: u32,
+}
+
+impl ConditionTexts {
+    /// Renders the texts whose conditions are met to an SeString, with player names linked to
+    /// the players as in chat. Npc names are left as plain text.
+    pub fn to_sestring<T>(&self, answers: &T, worlds: PlayerWorlds) -> SeString
+    where
+        T: Answers,
+    {
+        let mut parts = Vec::new();
+        let push_text = |parts: &mut Vec<SeStringPart>, text: &str| match parts.last_mut() {
+            Some(SeStringPart::Text(last)) => last.push_str(text),
+            _ => parts.push(SeStringPart::Text(text.to_string())),
+        };
+        for text in self.filter_map_texts(answers, |text| Some(text.clone())) {
+            match text {
+                Text::Static(s) => push_text(&mut parts, &s),
+                Text::Dynamic(d) => {
+                    let name = answers.as_str(&d);
+                    match d {
+                        DynamicText::PlayerOriginNameEn | DynamicText::PlayerOriginNameJp => {
+                            parts.extend(SeString::player_name(&name, worlds.origin).0)
+                        }
+                        DynamicText::PlayerTargetNameEn | DynamicText::PlayerTargetNameJp => {
+                            parts.extend(SeString::player_name(&name, worlds.target).0)
+                        }
+                        DynamicText::NpcOriginName | DynamicText::NpcTargetName => {
+                            push_text(&mut parts, &name)
+                        }
+                    }
+                }
+            }
+        }
+        SeString(parts)
+    }
+}
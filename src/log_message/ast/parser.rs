@@ -1,5 +1,6 @@
 use super::types::*;
 use crate::log_message::parser::{LogMessageParser, Rule};
+use crate::sestring::{decode_integer, expr};
 
 use pest_consume::{match_nodes, Error};
 use std::str::FromStr;
@@ -122,4 +123,61 @@ impl LogMessageParser {
             [message_part(parts).., _] => Message(parts.collect())
         ))
     }
+
+    fn color_tag_name(input: Node) -> Result<ColorTagName> {
+        ColorTagName::from_str(input.as_str()).map_err(|e| input.error(e))
+    }
+
+    /// The color of a color tag, or [None] for the StackColor expression.
+    fn color_value(input: Node) -> Result<Option<u32>> {
+        let bytes = (0..input.as_str().len())
+            .step_by(2)
+            .map(|i| {
+                input
+                    .as_str()
+                    .get(i..i + 2)
+                    .and_then(|b| u8::from_str_radix(b, 16).ok())
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| input.error("color value is not a sequence of hex bytes"))?;
+        if bytes == [expr::STACK_COLOR] {
+            return Ok(None);
+        }
+        decode_integer(&bytes)
+            .map(Some)
+            .ok_or_else(|| input.error("color value is not an integer expression"))
+    }
+
+    fn color_tag(input: Node) -> Result<ColorTag> {
+        // lose input when calling into_children, so create this in advance just in case
+        let nonmatch_err = Err(input.error("open and close tags do not match"));
+
+        Ok(match_nodes!(input.into_children();
+            [color_tag_name(name), color_value(color), color_tag_name(close_name)] => if name == close_name {
+                match color {
+                    Some(color) => ColorTag::Set { name, color },
+                    None => ColorTag::Reset { name },
+                }
+            } else {
+                return nonmatch_err;
+            }
+        ))
+    }
+
+    fn description_text(input: Node) -> Result<String> {
+        Ok(input.as_str().to_string())
+    }
+
+    fn description_part(input: Node) -> Result<DescriptionPart> {
+        Ok(match_nodes!(input.into_children();
+            [color_tag(tag)] => DescriptionPart::Color(tag),
+            [description_text(text)] => DescriptionPart::Text(text)
+        ))
+    }
+
+    pub fn description(input: Node) -> Result<Description> {
+        Ok(match_nodes!(input.into_children();
+            [description_part(parts).., _] => Description(parts.collect())
+        ))
+    }
 }
@@ -1,11 +1,14 @@
-use strum_macros::EnumString;
+use strum_macros::{AsRefStr, EnumDiscriminants, EnumString};
 use thiserror::Error;
-use tracing::*;
+
+#[cfg(feature = "json")]
+use serde_derive::{Deserialize, Serialize};
 
 use super::condition::{Condition, ConditionError, DynamicText, DynamicTextError};
 pub use super::condition_texts::ConditionTexts;
 
-#[derive(Debug, Clone, Error)]
+#[derive(Debug, Clone, Error, EnumDiscriminants)]
+#[strum_discriminants(name(EmoteTextProcessErrorKind), derive(Hash, PartialOrd, Ord))]
 pub enum EmoteTextProcessError {
     #[error("Function used in unexpected place ({name:?})")]
     DanglingFunction { name: FuncName },
@@ -37,20 +40,29 @@ pub enum EmoteTextProcessError {
     DynamicTextError(#[from] DynamicTextError),
 }
 
+/// With the `json` feature, serialized as `{ "static": "You look" }` or
+/// `{ "dynamic": "npc_origin_name" }`.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "snake_case"))]
 pub enum Text {
     Dynamic(DynamicText),
     Static(String),
 }
 
 // todo maybe come up with a better name...
+/// With the `json` feature, serialized as `{ "cond": "is_self_origin", "is_true": false }`.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
 pub struct ConditionState {
     pub cond: Condition,
     pub is_true: bool,
 }
 
+/// With the `json` feature, serialized as `{ "conds": [...], "text": {...} }`, where the text
+/// is output only when every [ConditionState] holds.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
 pub struct ConditionText {
     pub conds: Vec<ConditionState>,
     pub text: Text,
@@ -64,7 +76,7 @@ trait EmoteTextProcessor {
     ) -> Result<Vec<ConditionText>, EmoteTextProcessError>;
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Message(pub Vec<MessagePart>);
 
 impl Message {
@@ -78,7 +90,7 @@ impl Message {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum MessagePart {
     Element(Element),
     Text(String),
@@ -99,13 +111,13 @@ impl EmoteTextProcessor for MessagePart {
     }
 }
 
-#[derive(Debug, Clone, Copy, EnumString, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, EnumString, AsRefStr, PartialEq, Eq)]
 pub enum Obj {
     ObjStr,
     BNpcName,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Param {
     Element(Element),
     Function(Function),
@@ -127,14 +139,14 @@ impl EmoteTextProcessor for Param {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum IfElseThen {
     Function(Function),
     Element(Element),
     Text(String),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Element {
     IfElse(Box<IfElse>),
     Tag(Tag, Option<String>),
@@ -153,13 +165,13 @@ impl EmoteTextProcessor for Element {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum IfParam {
     Function(Function),
     Tag(Tag),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct IfElse {
     pub if_cond: IfParam,
     pub if_then: Vec<IfElseThen>,
@@ -220,14 +232,14 @@ impl EmoteTextProcessor for IfElse {
     }
 }
 
-#[derive(Debug, Clone, Copy, EnumString, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, EnumString, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum TagName {
     Clickable,
     Sheet,
     SheetEn,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Tag {
     pub name: TagName,
     pub params: Vec<Param>,
@@ -249,14 +261,14 @@ impl EmoteTextProcessor for Tag {
     }
 }
 
-#[derive(Debug, Clone, Copy, EnumString, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, EnumString, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum FuncName {
     Equal,
     ObjectParameter,
     PlayerParameter,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Function {
     pub name: FuncName,
     pub params: Vec<Param>,
@@ -273,3 +285,38 @@ impl EmoteTextProcessor for Function {
         }])
     }
 }
+
+#[derive(Debug, Clone, Copy, EnumString, PartialEq, Eq, Hash)]
+pub enum ColorTagName {
+    UIForeground,
+    UIGlow,
+}
+
+/// Sets or resets a text color, as found in command descriptions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorTag {
+    Set {
+        name: ColorTagName,
+        /// row of the UIColor sheet to push, or 0 to pop back to the previous color
+        color: u32,
+    },
+    /// pops back to the previous color, written with the StackColor expression (`EC`)
+    Reset { name: ColorTagName },
+}
+
+impl ColorTag {
+    pub fn name(&self) -> ColorTagName {
+        match self {
+            ColorTag::Set { name, .. } | ColorTag::Reset { name } => *name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DescriptionPart {
+    Color(ColorTag),
+    Text(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Description(pub Vec<DescriptionPart>);
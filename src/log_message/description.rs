@@ -0,0 +1,86 @@
+//! Command descriptions, the in-game help text for emote commands. These are plain text
+//! apart from UIForeground and UIGlow tags which change the text's color.
+
+use pest_consume::Parser;
+
+pub use super::ast::types::{ColorTag, ColorTagName, Description, DescriptionPart};
+use super::parser::{EmoteTextResult, LogMessageParser, Rule};
+use super::EmoteTextError;
+
+/// A run of description text sharing the same colors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StyledSegment {
+    pub text: String,
+    /// row of the UIColor sheet used for the text, if not the default
+    pub foreground: Option<u32>,
+    /// row of the UIColor sheet used for the text's outline, if not the default
+    pub glow: Option<u32>,
+}
+
+pub fn parse_description(description: &str) -> EmoteTextResult<Description> {
+    let root = LogMessageParser::parse(Rule::description, description)
+        .map_err(EmoteTextError::ParseError)?
+        .single()
+        .map_err(EmoteTextError::AstError)?;
+    LogMessageParser::description(root).map_err(EmoteTextError::AstError)
+}
+
+/// Strips color markup from a description.
+pub fn description_to_plain_text(description: &str) -> EmoteTextResult<String> {
+    Ok(parse_description(description)?.to_plain_text())
+}
+
+/// Splits a description into segments by color.
+pub fn description_to_segments(description: &str) -> EmoteTextResult<Vec<StyledSegment>> {
+    Ok(parse_description(description)?.to_segments())
+}
+
+impl Description {
+    pub fn to_plain_text(&self) -> String {
+        self.0
+            .iter()
+            .filter_map(|part| match part {
+                DescriptionPart::Text(text) => Some(text.as_str()),
+                DescriptionPart::Color(_) => None,
+            })
+            .collect()
+    }
+
+    pub fn to_segments(&self) -> Vec<StyledSegment> {
+        // colors are a stack in game, where 0 pops back to the previous color
+        let mut foregrounds = vec![];
+        let mut glows = vec![];
+        let mut segments: Vec<StyledSegment> = vec![];
+        for part in &self.0 {
+            match part {
+                DescriptionPart::Color(tag) => {
+                    let stack = match tag.name() {
+                        ColorTagName::UIForeground => &mut foregrounds,
+                        ColorTagName::UIGlow => &mut glows,
+                    };
+                    match tag {
+                        ColorTag::Set { color, .. } if *color != 0 => stack.push(*color),
+                        _ => {
+                            stack.pop();
+                        }
+                    }
+                }
+                DescriptionPart::Text(text) => {
+                    let foreground = foregrounds.last().copied();
+                    let glow = glows.last().copied();
+                    match segments.last_mut() {
+                        Some(last) if last.foreground == foreground && last.glow == glow => {
+                            last.text.push_str(text)
+                        }
+                        _ => segments.push(StyledSegment {
+                            text: text.clone(),
+                            foreground,
+                            glow,
+                        }),
+                    }
+                }
+            }
+        }
+        segments
+    }
+}
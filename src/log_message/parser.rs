@@ -3,7 +3,10 @@ use std::borrow::Cow;
 use pest_consume::Parser;
 
 pub use super::ast::types::{ConditionState, ConditionText, ConditionTexts, Text};
-use super::{ast::condition::Answers, EmoteTextError};
+use super::{
+    ast::{condition::Answers, types::Message, PlayerWorlds},
+    EmoteTextError,
+};
 
 #[derive(Parser)]
 #[grammar = "log_message/log_message.pest"]
@@ -22,21 +25,60 @@ where
     T: Answers,
 {
     let condition_texts = extract_condition_texts(log_msg)?;
+    Ok(render(&condition_texts, answers))
+}
+
+/// Like [process_log_message], but for the raw SeString bytes of a log message, such as those
+/// handed to plugins by the game, which are decoded without going through the macro text form.
+pub fn process_sestring_log_message<T>(bytes: &[u8], answers: &T) -> EmoteTextResult<String>
+where
+    T: Answers,
+{
+    let condition_texts = decode_message(bytes)?.process_string()?;
+    Ok(render(&condition_texts, answers))
+}
 
-    Ok(condition_texts
+/// Like [process_log_message], but producing the raw bytes of an SeString which can be sent as
+/// a chat payload, with player names linked to the players on the given worlds.
+pub fn process_log_message_to_sestring<T>(
+    log_msg: &str,
+    answers: &T,
+    worlds: PlayerWorlds,
+) -> EmoteTextResult<Vec<u8>>
+where
+    T: Answers,
+{
+    let condition_texts = extract_condition_texts(log_msg)?;
+    Ok(condition_texts.to_sestring(answers, worlds).encode())
+}
+
+fn render<T>(condition_texts: &ConditionTexts, answers: &T) -> String
+where
+    T: Answers,
+{
+    condition_texts
         .filter_map_texts(answers, |text| match text {
             Text::Dynamic(d) => Some(answers.as_str(d)),
             Text::Static(s) => Some(Cow::from(s.to_string())),
         })
-        .collect())
+        .collect()
 }
 
-pub fn extract_condition_texts(log_msg: &str) -> EmoteTextResult<ConditionTexts> {
+/// Parses the macro text form of a log message to its intermediate ast.
+pub fn parse_message(log_msg: &str) -> EmoteTextResult<Message> {
     let root = LogMessageParser::parse(Rule::message, log_msg)
         .map_err(EmoteTextError::ParseError)?
         .single()
         .map_err(EmoteTextError::AstError)?;
-    let message = LogMessageParser::message(root).map_err(EmoteTextError::AstError)?;
-    let condition_texts = message.process_string()?;
+    LogMessageParser::message(root).map_err(EmoteTextError::AstError)
+}
+
+/// Decodes the raw SeString bytes of a log message to its intermediate ast.
+pub fn decode_message(bytes: &[u8]) -> EmoteTextResult<Message> {
+    Ok(Message::decode(bytes)?)
+}
+
+pub fn extract_condition_texts(log_msg: &str) -> EmoteTextResult<ConditionTexts> {
+    let condition_texts = parse_message(log_msg)?.process_string()?;
     Ok(condition_texts)
 }
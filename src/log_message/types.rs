@@ -1,6 +1,12 @@
 use strum_macros::Display;
 
+#[cfg(feature = "json")]
+use serde_derive::{Deserialize, Serialize};
+
+/// With the `json` feature, serialized as `"male"` or `"female"`.
 #[derive(Debug, PartialEq, Eq, Clone, Display)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "lowercase"))]
 pub enum Gender {
     Male,
     Female,
@@ -0,0 +1,305 @@
+//! Python bindings through PyO3, importable as the `xiv_emote_parser` module.
+//!
+//! ```python
+//! from xiv_emote_parser import Character, Gender, LogMessageAnswers, LogMessageRepository, process_log_message
+//!
+//! repo = LogMessageRepository.from_json(json)
+//! answers = LogMessageAnswers(Character("Alice", Gender.Female), Character("Bob", Gender.Male, is_self=True))
+//! process_log_message(repo.targeted("/surprised", "en"), answers)
+//! ```
+
+// the code generated by #[pymethods] and #[pyfunction] converts results which are already PyResult
+#![allow(clippy::useless_conversion)]
+
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+use crate::{
+    log_message::{
+        self,
+        condition::{Character, Gender, LogMessageAnswers, LogMessageAnswersError},
+        EmoteTextError, EmoteTextErrorKind,
+    },
+    repository::{Language, LogMessageRepository, LogMessageRepositoryError},
+};
+
+/// The exceptions raised for the crate's errors, which are subclasses of [PyException].
+// create_exception! checks pyo3's gil-refs feature as if it were one of ours
+#[allow(unexpected_cfgs)]
+pub mod exceptions {
+    use pyo3::{create_exception, exceptions::PyException};
+
+    create_exception!(
+        xiv_emote_parser,
+        EmoteTextError,
+        PyException,
+        "A log message could not be processed."
+    );
+    create_exception!(
+        xiv_emote_parser,
+        ParseError,
+        EmoteTextError,
+        "A log message could not be parsed."
+    );
+    create_exception!(
+        xiv_emote_parser,
+        ProcessError,
+        EmoteTextError,
+        "A parsed log message contains an unsupported construct."
+    );
+    create_exception!(
+        xiv_emote_parser,
+        DecodeError,
+        EmoteTextError,
+        "An SeString log message could not be decoded."
+    );
+    create_exception!(
+        xiv_emote_parser,
+        LogMessageRepositoryError,
+        PyException,
+        "An emote or its log messages could not be loaded or found."
+    );
+    create_exception!(
+        xiv_emote_parser,
+        NotFoundError,
+        LogMessageRepositoryError,
+        "No emote has the command."
+    );
+    create_exception!(
+        xiv_emote_parser,
+        NoLogMessagesError,
+        LogMessageRepositoryError,
+        "The emote has no log messages in the language."
+    );
+    create_exception!(
+        xiv_emote_parser,
+        LogMessageAnswersError,
+        PyException,
+        "The characters of a message are inconsistent."
+    );
+}
+
+impl From<EmoteTextError> for PyErr {
+    fn from(e: EmoteTextError) -> PyErr {
+        let message = e.to_string();
+        match e.kind() {
+            EmoteTextErrorKind::ParseError
+            | EmoteTextErrorKind::AstError
+            | EmoteTextErrorKind::MessageParseError => exceptions::ParseError::new_err(message),
+            EmoteTextErrorKind::ProcessError(_) => exceptions::ProcessError::new_err(message),
+            EmoteTextErrorKind::DecodeError => exceptions::DecodeError::new_err(message),
+        }
+    }
+}
+
+impl From<LogMessageRepositoryError> for PyErr {
+    fn from(e: LogMessageRepositoryError) -> PyErr {
+        let message = e.to_string();
+        match e {
+            LogMessageRepositoryError::NotFound => exceptions::NotFoundError::new_err(message),
+            LogMessageRepositoryError::NoLogMessages => {
+                exceptions::NoLogMessagesError::new_err(message)
+            }
+            _ => exceptions::LogMessageRepositoryError::new_err(message),
+        }
+    }
+}
+
+impl From<LogMessageAnswersError> for PyErr {
+    fn from(e: LogMessageAnswersError) -> PyErr {
+        exceptions::LogMessageAnswersError::new_err(e.to_string())
+    }
+}
+
+fn language(code: &str) -> PyResult<Language> {
+    Language::from_code(code)
+        .ok_or_else(|| PyValueError::new_err(format!("Unknown language ({})", code)))
+}
+
+#[pyclass(name = "Gender", module = "xiv_emote_parser", eq, eq_int)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PyGender {
+    Male,
+    Female,
+}
+
+impl From<PyGender> for Gender {
+    fn from(gender: PyGender) -> Gender {
+        match gender {
+            PyGender::Male => Gender::Male,
+            PyGender::Female => Gender::Female,
+        }
+    }
+}
+
+impl From<&Gender> for PyGender {
+    fn from(gender: &Gender) -> PyGender {
+        match gender {
+            Gender::Male => PyGender::Male,
+            Gender::Female => PyGender::Female,
+        }
+    }
+}
+
+#[pyclass(name = "Character", module = "xiv_emote_parser", frozen)]
+#[derive(Debug, Clone)]
+pub struct PyCharacter(Character);
+
+#[pymethods]
+impl PyCharacter {
+    #[new]
+    #[pyo3(signature = (name, gender, is_pc = true, is_self = false))]
+    fn new(name: String, gender: PyGender, is_pc: bool, is_self: bool) -> PyCharacter {
+        PyCharacter(Character::new_from_string(
+            name,
+            gender.into(),
+            is_pc,
+            is_self,
+        ))
+    }
+
+    #[getter]
+    fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    #[getter]
+    fn gender(&self) -> PyGender {
+        (&self.0.gender).into()
+    }
+
+    #[getter]
+    fn is_pc(&self) -> bool {
+        self.0.is_pc
+    }
+
+    #[getter]
+    fn is_self(&self) -> bool {
+        self.0.is_self
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Character({:?}, Gender.{}, is_pc={}, is_self={})",
+            self.0.name,
+            self.0.gender,
+            if self.0.is_pc { "True" } else { "False" },
+            if self.0.is_self { "True" } else { "False" },
+        )
+    }
+}
+
+#[pyclass(name = "LogMessageAnswers", module = "xiv_emote_parser", frozen)]
+#[derive(Debug, Clone)]
+pub struct PyLogMessageAnswers(LogMessageAnswers);
+
+#[pymethods]
+impl PyLogMessageAnswers {
+    /// Answers for the origin and target, or for an untargeted message when there is no target.
+    #[new]
+    #[pyo3(signature = (origin, target = None))]
+    fn new(origin: PyCharacter, target: Option<PyCharacter>) -> PyResult<PyLogMessageAnswers> {
+        Ok(PyLogMessageAnswers(match target {
+            Some(target) => LogMessageAnswers::new(origin.0, target.0)?,
+            None => LogMessageAnswers::untargeted(origin.0),
+        }))
+    }
+
+    #[getter]
+    fn origin(&self) -> PyCharacter {
+        PyCharacter(self.0.origin_character().clone())
+    }
+
+    #[getter]
+    fn target(&self) -> PyCharacter {
+        PyCharacter(self.0.target_character().clone())
+    }
+}
+
+#[pyclass(name = "LogMessageRepository", module = "xiv_emote_parser", frozen)]
+pub struct PyLogMessageRepository(LogMessageRepository);
+
+#[pymethods]
+impl PyLogMessageRepository {
+    /// Loads a repository from a json list of emotes.
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<PyLogMessageRepository> {
+        Ok(PyLogMessageRepository(LogMessageRepository::from_json(
+            json,
+        )?))
+    }
+
+    /// The targeted log message of the emote with the command, in the language with the code.
+    fn targeted(&self, command: &str, language: &str) -> PyResult<String> {
+        Ok(self
+            .0
+            .targeted(command, self::language(language)?)?
+            .to_string())
+    }
+
+    /// The untargeted log message of the emote with the command, in the language with the code.
+    fn untargeted(&self, command: &str, language: &str) -> PyResult<String> {
+        Ok(self
+            .0
+            .untargeted(command, self::language(language)?)?
+            .to_string())
+    }
+
+    fn contains_emote(&self, command: &str) -> bool {
+        self.0.contains_emote(command)
+    }
+
+    /// Every command of every emote, sorted by emote id.
+    fn emote_list(&self) -> Vec<String> {
+        self.0.emote_list_by_id().cloned().collect()
+    }
+
+    fn __contains__(&self, command: &str) -> bool {
+        self.0.contains_emote(command)
+    }
+}
+
+/// Renders a log message for the characters of the answers.
+#[pyfunction]
+fn process_log_message(log_msg: &str, answers: &PyLogMessageAnswers) -> PyResult<String> {
+    Ok(log_message::process_log_message(log_msg, &answers.0)?)
+}
+
+#[pymodule]
+pub fn xiv_emote_parser(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add_class::<PyGender>()?;
+    m.add_class::<PyCharacter>()?;
+    m.add_class::<PyLogMessageAnswers>()?;
+    m.add_class::<PyLogMessageRepository>()?;
+    m.add_function(wrap_pyfunction!(process_log_message, m)?)?;
+    m.add(
+        "EmoteTextError",
+        py.get_type_bound::<exceptions::EmoteTextError>(),
+    )?;
+    m.add("ParseError", py.get_type_bound::<exceptions::ParseError>())?;
+    m.add(
+        "ProcessError",
+        py.get_type_bound::<exceptions::ProcessError>(),
+    )?;
+    m.add(
+        "DecodeError",
+        py.get_type_bound::<exceptions::DecodeError>(),
+    )?;
+    m.add(
+        "LogMessageRepositoryError",
+        py.get_type_bound::<exceptions::LogMessageRepositoryError>(),
+    )?;
+    m.add(
+        "NotFoundError",
+        py.get_type_bound::<exceptions::NotFoundError>(),
+    )?;
+    m.add(
+        "NoLogMessagesError",
+        py.get_type_bound::<exceptions::NoLogMessagesError>(),
+    )?;
+    m.add(
+        "LogMessageAnswersError",
+        py.get_type_bound::<exceptions::LogMessageAnswersError>(),
+    )?;
+    Ok(())
+}
@@ -1,45 +1,229 @@
 #[cfg(feature = "json")]
 use {serde_derive::Deserialize, serde_json};
 
+#[cfg(feature = "json")]
+use std::time::Duration;
+
 #[cfg(feature = "xivapi")]
-use {std::time::Duration, ureq};
+use ureq;
 
-use std::collections::HashMap;
+use std::collections::{hash_map::Entry, HashMap};
 use std::sync::Arc;
 use tracing::*;
 
 use thiserror::Error;
 
+mod diff;
+#[cfg(feature = "sqpack")]
+mod game_data;
+#[cfg(feature = "json")]
+mod report;
+#[cfg(feature = "csv")]
+mod saintcoinach;
+mod search;
+mod shared;
+#[cfg(any(feature = "csv", feature = "sqpack"))]
+mod sheets;
+mod validate;
+#[cfg(feature = "json")]
+mod view;
+
+pub use self::diff::{EmoteChange, MessageChange, MessageKind, RepositoryDiff};
+#[cfg(feature = "sqpack")]
+pub use self::game_data::SheetColumns;
+#[cfg(feature = "json")]
+pub use self::report::{LoadReport, MissingField, PartialEmote, SkipReason, SkippedEmote};
+#[cfg(feature = "csv")]
+pub use self::saintcoinach::CsvSheets;
+pub use self::search::{MatchKind, SearchMatch};
+pub use self::shared::SharedLogMessageRepository;
+pub use self::validate::{Construct, ValidationFailure, ValidationReport};
+#[cfg(feature = "json")]
+pub use self::view::{EmoteView, MessagesView};
+
 #[derive(Debug, Error)]
+#[allow(clippy::large_enum_variant)]
 pub enum LogMessageRepositoryError {
     #[error("Message not found")]
     NotFound,
+    #[error("Emote has no log messages")]
+    NoLogMessages,
     #[cfg(feature = "json")]
     #[error("Invalid json input string")]
     InvalidJsonInput(#[from] serde_json::Error),
     #[cfg(feature = "xivapi")]
     #[error("A network error occurred")]
     Network(#[from] ureq::Error),
-    #[cfg(feature = "xivapi")]
+    #[cfg(feature = "xivapi-async")]
+    #[error("A network error occurred")]
+    AsyncNetwork(#[from] reqwest::Error),
+    #[cfg(any(feature = "xivapi", feature = "xivapi-async"))]
     #[error("Request limit reached, wait before trying again")]
     RequestLimit,
-    #[cfg(feature = "xivapi")]
-    #[error("Io error while requesting data")]
+    #[cfg(any(feature = "xivapi", feature = "csv"))]
+    #[error("Io error while requesting or reading data")]
     Io(#[from] std::io::Error),
+    #[cfg(feature = "csv")]
+    #[error("Invalid csv input")]
+    Csv(#[from] csv::Error),
+    #[cfg(feature = "sqpack")]
+    #[error("Could not read game files")]
+    SqPack(#[from] crate::sqpack::SqPackError),
+    #[cfg(any(feature = "csv", feature = "sqpack"))]
+    #[error("Invalid {sheet} sheet ({reason})")]
+    InvalidSheet { sheet: &'static str, reason: String },
 }
 
-// a conservative limit, but emotes should not require more than a small handful of pages
-#[cfg(feature = "xivapi")]
+/// Number of pages loaded after which further page requests wait [XIVAPI_THROTTLE_DELAY] first.
+/// Retries of a failed page don't count towards it. This is conservative, but emotes should not
+/// require more than a small handful of pages.
+#[cfg(any(feature = "xivapi", feature = "xivapi-async"))]
 pub const XIVAPI_REQUEST_LIMIT: u32 = 15;
 
+/// Delay before each page request once [XIVAPI_REQUEST_LIMIT] pages have been loaded.
+#[cfg(any(feature = "xivapi", feature = "xivapi-async"))]
+pub const XIVAPI_THROTTLE_DELAY: Duration = Duration::from_secs(2);
+
+#[cfg(feature = "json")]
+pub const XIVAPI_EMOTE_URL: &str = "https://xivapi.com/emote";
+
+/// Settings for how requests to xivapi are made and retried.
+///
+/// Requests that fail with HTTP 429 (Too Many Requests) or a 5xx status are retried
+/// with exponential backoff, honoring the Retry-After header when xivapi sends one.
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XivapiConfig {
+    /// url of the emote endpoint
+    pub url: String,
+    /// timeout for each individual request
+    pub timeout: Duration,
+    /// total number of retries allowed while loading all pages, after which
+    /// `LogMessageRepositoryError::RequestLimit` is returned
+    pub retry_budget: u32,
+    /// delay before the first retry of a page, doubled for each consecutive retry
+    pub initial_backoff: Duration,
+    /// upper bound for the backoff between retries, which does not apply to the delay asked for
+    /// by a Retry-After header
+    pub max_backoff: Duration,
+    /// whether to keep emotes which are missing their log messages, such as motion-only
+    /// emotes, with [EmoteData::en] and [EmoteData::ja] left empty
+    pub include_motion_only: bool,
+}
+
+#[cfg(feature = "json")]
+impl Default for XivapiConfig {
+    fn default() -> Self {
+        XivapiConfig {
+            url: XIVAPI_EMOTE_URL.to_string(),
+            timeout: Duration::from_secs(30),
+            retry_budget: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+            include_motion_only: false,
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl XivapiConfig {
+    /// The delay before the given (zero-indexed) consecutive retry of a page.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .checked_mul(2u32.saturating_pow(attempt))
+            .map_or(self.max_backoff, |d| d.min(self.max_backoff))
+    }
+
+    #[cfg(any(feature = "xivapi", feature = "xivapi-async"))]
+    fn is_retryable(status: u16) -> bool {
+        status == 429 || (500..600).contains(&status)
+    }
+
+    /// Uses the Retry-After header (in seconds) if present, otherwise the backoff for this attempt.
+    #[cfg(any(feature = "xivapi", feature = "xivapi-async"))]
+    fn retry_delay(&self, retry_after: Option<&str>, attempt: u32) -> Duration {
+        retry_after
+            .and_then(|secs| secs.trim().parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or_else(|| self.backoff(attempt))
+    }
+}
+
 pub type Result<T> = std::result::Result<T, LogMessageRepositoryError>;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct EmoteData {
     pub id: u32,
     pub name: String,
-    pub en: LogMessagePair,
-    pub ja: LogMessagePair,
+    /// the emote's name in each language it is known in
+    pub names: HashMap<Language, String>,
+    /// every command, short command, alias and short alias for the emote, as written in game
+    pub commands: Vec<String>,
+    /// the in-game help text for the emote's command in each language, which may contain
+    /// UIForeground and UIGlow markup
+    pub descriptions: HashMap<Language, String>,
+    pub category: Option<EmoteCategory>,
+    /// id of the emote's icon
+    pub icon: Option<u32>,
+    /// id of the unlock link required to use the emote, where 0 means the emote is always available
+    pub unlock_link: Option<u32>,
+    /// LogKind of the emote's log messages, which determines the chat channel they appear in
+    pub log_kind: Option<u32>,
+    /// the emote's log messages, which motion-only emotes do not have
+    pub en: Option<LogMessagePair>,
+    pub ja: Option<LogMessagePair>,
+    /// only available from a local dump, see `LogMessageRepository::load_language_dump`
+    pub chs: Option<LogMessagePair>,
+    /// only available from a local dump, see `LogMessageRepository::load_language_dump`
+    pub ko: Option<LogMessagePair>,
+}
+
+impl EmoteData {
+    pub fn requires_unlock(&self) -> bool {
+        self.unlock_link.is_some_and(|link| link != 0)
+    }
+
+    pub fn name_in(&self, language: Language) -> Option<&str> {
+        self.names.get(&language).map(String::as_str)
+    }
+
+    pub fn description_in(&self, language: Language) -> Option<&str> {
+        self.descriptions.get(&language).map(String::as_str)
+    }
+
+    pub fn messages_in(&self, language: Language) -> Option<&LogMessagePair> {
+        match language {
+            Language::En => self.en.as_ref(),
+            Language::Ja => self.ja.as_ref(),
+            Language::Chs => self.chs.as_ref(),
+            Language::Ko => self.ko.as_ref(),
+        }
+    }
+
+    #[cfg(feature = "json")]
+    fn messages_in_mut(&mut self, language: Language) -> &mut Option<LogMessagePair> {
+        match language {
+            Language::En => &mut self.en,
+            Language::Ja => &mut self.ja,
+            Language::Chs => &mut self.chs,
+            Language::Ko => &mut self.ko,
+        }
+    }
+}
+
+/// The tab an emote is listed under in the emote window, such as General or Expressions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(Deserialize))]
+pub struct EmoteCategory {
+    pub id: u32,
+    #[cfg_attr(feature = "json", serde(default))]
+    pub names: HashMap<Language, String>,
+}
+
+impl EmoteCategory {
+    pub fn name_in(&self, language: Language) -> Option<&str> {
+        self.names.get(&language).map(String::as_str)
+    }
 }
 
 impl Ord for EmoteData {
@@ -54,20 +238,160 @@ impl PartialOrd for EmoteData {
     }
 }
 
+/// An emote's log messages in one language, where either message may be missing from a
+/// partially loaded emote, see `LoadReport::partial`.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "json", derive(Deserialize))]
 pub struct LogMessagePair {
-    pub targeted: String,
-    pub untargeted: String,
+    pub targeted: Option<String>,
+    pub untargeted: Option<String>,
+}
+
+impl LogMessagePair {
+    /// A pair of whichever messages exist, or [None] if neither does.
+    pub fn new(targeted: Option<String>, untargeted: Option<String>) -> Option<LogMessagePair> {
+        (targeted.is_some() || untargeted.is_some()).then_some(LogMessagePair {
+            targeted,
+            untargeted,
+        })
+    }
+
+    pub fn get(&self, kind: MessageKind) -> Option<&str> {
+        match kind {
+            MessageKind::Targeted => self.targeted.as_deref(),
+            MessageKind::Untargeted => self.untargeted.as_deref(),
+        }
+    }
 }
 
 pub type MessagesMap = HashMap<String, Arc<EmoteData>>;
 
+/// Converts a command to the form used as a key in [MessagesMap], so that lookups ignore case,
+/// full-width characters, surrounding whitespace and a missing leading slash.
+///
+/// For example, `Surprised`, `/SURPRISED` and `／ｓｕｒｐｒｉｓｅｄ` all normalize to `/surprised`.
+pub fn normalize_command(command: &str) -> String {
+    let folded: String = command
+        .chars()
+        .map(|c| match c {
+            // full-width forms of ascii characters
+            '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            '\u{3000}' => ' ',
+            _ => c,
+        })
+        .flat_map(char::to_lowercase)
+        .collect();
+    format!("/{}", folded.trim().trim_start_matches('/'))
+}
+
+/// Commands of different emotes which normalize to the same key, see [normalize_command].
+/// The emote with the lowest id keeps the command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandCollision {
+    /// the normalized command
+    pub command: String,
+    /// id of the emote the command resolves to
+    pub kept: u32,
+    /// id of the emote whose command was dropped
+    pub dropped: u32,
+}
+
+/// Keys the messages by their normalized commands, along with the [EmoteData::commands] of
+/// each emote, recording any commands which collide.
+fn normalize_keys(messages: MessagesMap) -> (MessagesMap, Vec<CommandCollision>) {
+    let commands: Vec<_> = messages
+        .values()
+        .flat_map(|data| {
+            data.commands
+                .iter()
+                .map(|command| (command.clone(), data.clone()))
+        })
+        .collect();
+    index_commands(messages.into_iter().chain(commands))
+}
+
+fn index_commands(
+    entries: impl IntoIterator<Item = (String, Arc<EmoteData>)>,
+) -> (MessagesMap, Vec<CommandCollision>) {
+    let mut messages = MessagesMap::new();
+    let mut collisions = Vec::new();
+    for (command, data) in entries {
+        match messages.entry(normalize_command(&command)) {
+            Entry::Vacant(entry) => {
+                entry.insert(data);
+            }
+            Entry::Occupied(mut entry) if entry.get().id != data.id => {
+                let dropped = if data.id < entry.get().id {
+                    entry.insert(data).id
+                } else {
+                    data.id
+                };
+                collisions.push(CommandCollision {
+                    command: entry.key().clone(),
+                    kept: 0,
+                    dropped,
+                });
+            }
+            Entry::Occupied(_) => {}
+        }
+    }
+    for collision in &mut collisions {
+        collision.kept = messages[&collision.command].id;
+        warn!(
+            "command {} of emote {} collides with emote {}",
+            collision.command, collision.dropped, collision.kept
+        );
+    }
+    collisions.sort_unstable_by(|a, b| (&a.command, a.dropped).cmp(&(&b.command, b.dropped)));
+    collisions.dedup();
+    (messages, collisions)
+}
+
+/// Secondary indexes over a [MessagesMap], rebuilt whenever the messages change.
+#[derive(Debug, Clone, Default)]
+struct EmoteIndex {
+    by_id: HashMap<u32, Arc<EmoteData>>,
+    by_name: HashMap<Language, HashMap<String, Arc<EmoteData>>>,
+    /// every command, sorted for prefix completion
+    commands: Vec<String>,
+}
+
+impl EmoteIndex {
+    fn new(messages: &MessagesMap) -> EmoteIndex {
+        let mut commands: Vec<_> = messages.keys().cloned().collect();
+        commands.sort_unstable();
+        let index = EmoteIndex {
+            commands,
+            ..Default::default()
+        };
+        messages.values().fold(index, |mut index, data| {
+            index.by_id.insert(data.id, data.clone());
+            for (language, name) in &data.names {
+                index
+                    .by_name
+                    .entry(*language)
+                    .or_default()
+                    .insert(name.clone(), data.clone());
+            }
+            index
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct LogMessageRepository {
     messages: MessagesMap,
-    #[cfg(feature = "xivapi")]
+    index: EmoteIndex,
+    collisions: Vec<CommandCollision>,
+    /// language dumps merged into the messages, see [LogMessageRepository::load_language_dump]
+    #[cfg(feature = "json")]
+    language_dumps: Vec<(Language, Vec<LanguageDumpData>)>,
+    #[cfg(any(feature = "xivapi", feature = "xivapi-async"))]
     query: Vec<(String, String)>,
+    #[cfg(feature = "json")]
+    xivapi_config: XivapiConfig,
+    #[cfg(feature = "json")]
+    report: LoadReport,
 }
 
 impl LogMessageRepository {
@@ -76,18 +400,24 @@ impl LogMessageRepository {
         let messages = serde_json::from_str::<Vec<LogMessageData>>(json)
             .map_err(LogMessageRepositoryError::InvalidJsonInput)?
             .into_iter()
-            .fold(HashMap::new(), |mut map, data| {
+            .fold(HashMap::new(), |mut map, mut data| {
+                data.names
+                    .entry(Language::En)
+                    .or_insert_with(|| data.name.clone());
                 let value = Arc::new(EmoteData {
                     id: data.id,
                     name: data.name,
-                    en: LogMessagePair {
-                        targeted: data.en.targeted,
-                        untargeted: data.en.untargeted,
-                    },
-                    ja: LogMessagePair {
-                        targeted: data.ja.targeted,
-                        untargeted: data.ja.untargeted,
-                    },
+                    names: data.names,
+                    commands: data.commands.clone(),
+                    descriptions: data.descriptions,
+                    category: data.category,
+                    icon: data.icon,
+                    unlock_link: data.unlock_link,
+                    log_kind: data.log_kind,
+                    en: data.en,
+                    ja: data.ja,
+                    chs: data.chs,
+                    ko: data.ko,
                 });
                 for command in data.commands {
                     trace!("{} => {}", command, value.name);
@@ -95,20 +425,191 @@ impl LogMessageRepository {
                 }
                 map
             });
-        Ok(LogMessageRepository {
+        Ok(Self::new(messages))
+    }
+
+    /// Merges a json dump of a single client language into the repository, matching emotes by id.
+    /// The dump is a list of [LanguageDumpData], and its commands are added alongside the
+    /// existing ones. Emotes which are only in the dump are added with just that language, unless
+    /// they have no commands to look them up by, in which case they are left out.
+    ///
+    /// Messages missing from the dump leave any existing ones in place. The dump is kept and
+    /// merged again after the messages are reloaded from xivapi, replacing any earlier dump of
+    /// the same language. Emotes in the dump missing some of their messages are listed in the
+    /// load report.
+    #[cfg(feature = "json")]
+    pub fn load_language_dump(&mut self, language: Language, json: &str) -> Result<()> {
+        let dump = serde_json::from_str::<Vec<LanguageDumpData>>(json)
+            .map_err(LogMessageRepositoryError::InvalidJsonInput)?;
+        self.merge_language_dump(language, &dump);
+        match self
+            .language_dumps
+            .iter_mut()
+            .find(|(loaded, _)| *loaded == language)
+        {
+            Some((_, loaded)) => *loaded = dump,
+            None => self.language_dumps.push((language, dump)),
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "json")]
+    fn merge_language_dump(&mut self, language: Language, dump: &[LanguageDumpData]) {
+        let mut emotes: HashMap<u32, EmoteData> = self
+            .emotes()
+            .map(|data| (data.id, data.as_ref().clone()))
+            .collect();
+        self.report
+            .partial
+            .retain(|partial| partial.languages != [language]);
+        for entry in dump {
+            if entry.commands.is_empty() && !emotes.contains_key(&entry.id) {
+                debug!(
+                    "leaving out emote {} of the dump, which has no commands",
+                    entry.id
+                );
+                continue;
+            }
+            let data = emotes.entry(entry.id).or_insert_with(|| EmoteData {
+                id: entry.id,
+                name: entry.name.clone().unwrap_or_default(),
+                names: HashMap::new(),
+                commands: Vec::new(),
+                descriptions: HashMap::new(),
+                category: None,
+                icon: None,
+                unlock_link: None,
+                log_kind: None,
+                en: None,
+                ja: None,
+                chs: None,
+                ko: None,
+            });
+            if let Some(name) = &entry.name {
+                data.names.insert(language, name.clone());
+            }
+            if let Some(description) = &entry.description {
+                data.descriptions.insert(language, description.clone());
+            }
+            for command in &entry.commands {
+                if !data.commands.contains(command) {
+                    data.commands.push(command.clone());
+                }
+            }
+            let pair = data.messages_in_mut(language);
+            match pair {
+                Some(pair) => {
+                    if let Some(targeted) = &entry.targeted {
+                        pair.targeted = Some(targeted.clone());
+                    }
+                    if let Some(untargeted) = &entry.untargeted {
+                        pair.untargeted = Some(untargeted.clone());
+                    }
+                }
+                None => {
+                    *pair = LogMessagePair::new(entry.targeted.clone(), entry.untargeted.clone())
+                }
+            }
+            let missing: Vec<_> = [
+                (MissingField::LogMessageTargeted, entry.targeted.is_none()),
+                (
+                    MissingField::LogMessageUntargeted,
+                    entry.untargeted.is_none(),
+                ),
+            ]
+            .into_iter()
+            .filter_map(|(field, is_missing)| is_missing.then_some(field))
+            .collect();
+            if !missing.is_empty() {
+                self.report.partial.push(PartialEmote {
+                    id: data.id,
+                    name: data.name.clone(),
+                    languages: vec![language],
+                    missing,
+                });
+            }
+        }
+        let emotes: HashMap<u32, Arc<EmoteData>> = emotes
+            .into_iter()
+            .map(|(id, data)| (id, Arc::new(data)))
+            .collect();
+        // existing keys are kept even when they are not one of the emote's commands
+        let existing = self
+            .messages
+            .iter()
+            .map(|(key, data)| (key.clone(), emotes[&data.id].clone()));
+        let commands = emotes.values().flat_map(|data| {
+            data.commands
+                .iter()
+                .map(|command| (command.clone(), data.clone()))
+        });
+        let (messages, collisions) = index_commands(existing.chain(commands).collect::<Vec<_>>());
+        self.index = EmoteIndex::new(&messages);
+        self.messages = messages;
+        self.collisions = collisions;
+    }
+
+    /// Merges the loaded language dumps again, after the messages were replaced.
+    #[cfg(any(feature = "xivapi", feature = "xivapi-async"))]
+    fn merge_language_dumps(&mut self) {
+        let dumps = std::mem::take(&mut self.language_dumps);
+        for (language, dump) in &dumps {
+            self.merge_language_dump(*language, dump);
+        }
+        self.language_dumps = dumps;
+    }
+
+    /// Builds a repository from messages that have already been loaded, keyed by command.
+    /// Commands are normalized with [normalize_command], and see
+    /// [LogMessageRepository::command_collisions] for commands shared by several emotes.
+    pub fn new(messages: MessagesMap) -> LogMessageRepository {
+        let (messages, collisions) = normalize_keys(messages);
+        LogMessageRepository {
+            index: EmoteIndex::new(&messages),
             messages,
-            #[cfg(feature = "xivapi")]
+            collisions,
+            #[cfg(feature = "json")]
+            language_dumps: Vec::new(),
+            #[cfg(any(feature = "xivapi", feature = "xivapi-async"))]
             query: Vec::with_capacity(3),
-        })
+            #[cfg(feature = "json")]
+            xivapi_config: XivapiConfig::default(),
+            #[cfg(feature = "json")]
+            report: LoadReport::default(),
+        }
     }
 
-    #[cfg(feature = "xivapi")]
+    #[cfg(any(feature = "xivapi", feature = "xivapi-async"))]
+    fn set_messages(&mut self, (messages, report): (MessagesMap, LoadReport)) {
+        let (messages, collisions) = normalize_keys(messages);
+        self.index = EmoteIndex::new(&messages);
+        self.messages = messages;
+        self.collisions = collisions;
+        self.report = report;
+        self.merge_language_dumps();
+    }
+
+    #[cfg(any(feature = "xivapi", feature = "xivapi-async"))]
     pub fn prep_xivapi_query(api_key: Option<String>) -> Vec<(String, String)> {
         let mut query = Vec::with_capacity(3);
         query.push(("snake_case".to_string(), "1".to_string()));
         query.push((
             "columns".to_string(),
-            "LogMessageTargeted,LogMessageUntargeted,Name,TextCommand,ID".to_string(),
+            [
+                "LogMessageTargeted",
+                "LogMessageUntargeted",
+                "Name",
+                "Name_en",
+                "Name_ja",
+                "TextCommand",
+                "ID",
+                "EmoteCategory.ID",
+                "EmoteCategory.Name_en",
+                "EmoteCategory.Name_ja",
+                "IconID",
+                "UnlockLink",
+            ]
+            .join(","),
         ));
         if let Some(key) = api_key {
             trace!("adding xivapi private key");
@@ -119,122 +620,424 @@ impl LogMessageRepository {
 
     #[cfg(feature = "xivapi")]
     pub fn from_xivapi(api_key: Option<String>) -> Result<LogMessageRepository> {
+        Self::from_xivapi_with_config(api_key, XivapiConfig::default())
+    }
+
+    #[cfg(feature = "xivapi")]
+    pub fn from_xivapi_with_config(
+        api_key: Option<String>,
+        xivapi_config: XivapiConfig,
+    ) -> Result<LogMessageRepository> {
         let query = Self::prep_xivapi_query(api_key);
+        let (messages, report) = Self::parse_xivapi(
+            Self::load_xivapi_with_config(&query, &xivapi_config)?,
+            &xivapi_config,
+        );
         Ok(LogMessageRepository {
-            messages: Self::parse_xivapi(Self::load_xivapi(&query)?),
             query,
+            xivapi_config,
+            report,
+            ..Self::new(messages)
         })
     }
 
-    #[cfg(feature = "xivapi")]
-    fn parse_xivapi(results: Vec<self::xivapi::EmoteData>) -> MessagesMap {
-        results
-            .into_iter()
-            .fold::<MessagesMap, _>(HashMap::new(), |mut map, result| {
+    #[cfg(feature = "json")]
+    fn parse_xivapi(
+        results: Vec<self::xivapi::EmoteData>,
+        config: &XivapiConfig,
+    ) -> (MessagesMap, LoadReport) {
+        results.into_iter().fold(
+            (HashMap::new(), LoadReport::default()),
+            |(mut map, mut report), result| {
                 debug!("processing from xivapi: {:?}", result);
-                if let self::xivapi::EmoteData {
-                    log_message_targeted: Some(targeted),
-                    log_message_untargeted: Some(untargeted),
-                    text_command: Some(text_command),
-                    name: Some(name),
-                    id: Some(id),
-                } = result
-                {
-                    let data = Arc::new(EmoteData {
-                        id,
-                        name,
-                        en: LogMessagePair {
-                            targeted: targeted.text_en,
-                            untargeted: untargeted.text_en,
-                        },
-                        ja: LogMessagePair {
-                            targeted: targeted.text_ja,
-                            untargeted: untargeted.text_ja,
-                        },
-                    });
-                    [
-                        text_command.alias_en,
-                        text_command.alias_ja,
-                        text_command.command_en,
-                        text_command.command_ja,
-                    ]
+                let self::xivapi::EmoteData {
+                    log_message_targeted: targeted,
+                    log_message_untargeted: untargeted,
+                    text_command,
+                    name,
+                    name_en,
+                    name_ja,
+                    id,
+                    emote_category,
+                    icon_id,
+                    unlock_link,
+                } = result;
+                let skip = |reason| SkippedEmote {
+                    id,
+                    name: name.clone(),
+                    reason,
+                };
+                let (id, name, text_command) = match (id, name.clone(), text_command) {
+                    (None, _, _) => {
+                        report.skipped.push(skip(SkipReason::MissingId));
+                        return (map, report);
+                    }
+                    (_, None, _) => {
+                        report.skipped.push(skip(SkipReason::MissingName));
+                        return (map, report);
+                    }
+                    (_, _, None) => {
+                        report.skipped.push(skip(SkipReason::MissingTextCommand));
+                        return (map, report);
+                    }
+                    (Some(id), Some(name), Some(text_command)) => (id, name, text_command),
+                };
+                let missing: Vec<_> = [
+                    (MissingField::LogMessageTargeted, targeted.is_none()),
+                    (MissingField::LogMessageUntargeted, untargeted.is_none()),
+                ]
+                .into_iter()
+                .filter_map(|(field, is_missing)| is_missing.then_some(field))
+                .collect();
+                if !missing.is_empty() && !config.include_motion_only {
+                    trace!("skipping {} due to no messages", name);
+                    report
+                        .skipped
+                        .push(skip(SkipReason::MissingLogMessages(missing)));
+                    return (map, report);
+                }
+                // xivapi's default language for name is en
+                let names = Self::localized([
+                    (Language::En, name_en.or_else(|| Some(name.clone()))),
+                    (Language::Ja, name_ja),
+                ]);
+                let descriptions = Self::localized([
+                    (Language::En, text_command.description_en),
+                    (Language::Ja, text_command.description_ja),
+                ]);
+                let commands = [
+                    text_command.command_en,
+                    text_command.short_command_en,
+                    text_command.alias_en,
+                    text_command.short_alias_en,
+                    text_command.command_ja,
+                    text_command.short_command_ja,
+                    text_command.alias_ja,
+                    text_command.short_alias_ja,
+                ]
+                .into_iter()
+                .flatten()
+                .filter(|cmd| !cmd.is_empty())
+                .fold(Vec::new(), |mut commands, cmd| {
+                    if !commands.contains(&cmd) {
+                        commands.push(cmd);
+                    }
+                    commands
+                });
+                if commands.is_empty() {
+                    report.skipped.push(skip(SkipReason::NoCommands));
+                    return (map, report);
+                }
+                let category = emote_category.and_then(|category| {
+                    Some(EmoteCategory {
+                        id: category.id?,
+                        names: Self::localized([
+                            (Language::En, category.name_en),
+                            (Language::Ja, category.name_ja),
+                        ]),
+                    })
+                });
+                let log_kind = [&targeted, &untargeted]
                     .into_iter()
                     .flatten()
-                    .filter(|cmd| !cmd.is_empty())
-                    .for_each(|cmd| {
-                        trace!("{} => {}", cmd, data.name);
-                        map.insert(cmd, data.clone());
-                    })
-                } else {
-                    trace!("ignoring invalid emote data ({:?})", result);
+                    .find_map(|message| message.log_kind);
+                // partial emotes keep whichever of their messages loaded
+                let (targeted_en, targeted_ja) = targeted
+                    .map(|message| (message.text_en, message.text_ja))
+                    .unzip();
+                let (untargeted_en, untargeted_ja) = untargeted
+                    .map(|message| (message.text_en, message.text_ja))
+                    .unzip();
+                let en = LogMessagePair::new(targeted_en, untargeted_en);
+                let ja = LogMessagePair::new(targeted_ja, untargeted_ja);
+                if !missing.is_empty() {
+                    report.partial.push(PartialEmote {
+                        id,
+                        name: name.clone(),
+                        languages: vec![Language::En, Language::Ja],
+                        missing,
+                    });
                 }
-                map
-            })
+                let data = Arc::new(EmoteData {
+                    id,
+                    name,
+                    names,
+                    commands: commands.clone(),
+                    descriptions,
+                    category,
+                    icon: icon_id,
+                    unlock_link,
+                    log_kind,
+                    en,
+                    ja,
+                    chs: None,
+                    ko: None,
+                });
+                for cmd in commands {
+                    trace!("{} => {}", cmd, data.name);
+                    map.insert(cmd, data.clone());
+                }
+                (map, report)
+            },
+        )
+    }
+
+    /// Collects the non-empty values into a map by language.
+    #[cfg(feature = "json")]
+    fn localized<const N: usize>(
+        values: [(Language, Option<String>); N],
+    ) -> HashMap<Language, String> {
+        values
+            .into_iter()
+            .filter_map(|(language, value)| Some((language, value?)))
+            .filter(|(_, value)| !value.is_empty())
+            .collect()
     }
 
     #[cfg(feature = "xivapi")]
     pub fn load_xivapi(query: &[(String, String)]) -> Result<Vec<self::xivapi::EmoteData>> {
-        let agent = ureq::AgentBuilder::new().build();
+        Self::load_xivapi_with_config(query, &XivapiConfig::default())
+    }
+
+    /// Loads every page of emotes from xivapi, retrying failed requests as described by
+    /// [XivapiConfig].
+    #[cfg(feature = "xivapi")]
+    pub fn load_xivapi_with_config(
+        query: &[(String, String)],
+        config: &XivapiConfig,
+    ) -> Result<Vec<self::xivapi::EmoteData>> {
+        let agent = ureq::AgentBuilder::new().timeout(config.timeout).build();
         let mut results = Vec::new();
-        let mut req_count = 0;
+        let mut page = 1;
+        let mut pages = 0;
+        let mut retries = 0;
+        let mut attempt = 0;
         loop {
-            req_count += 1;
-            if req_count >= XIVAPI_REQUEST_LIMIT {
-                std::thread::sleep(Duration::from_secs(2));
+            if pages >= XIVAPI_REQUEST_LIMIT {
+                std::thread::sleep(XIVAPI_THROTTLE_DELAY);
             }
-            let page_query = ("page".to_string(), req_count.to_string());
-            debug!("loading page {}", page_query.1);
-            let mut req = agent.get("https://xivapi.com/emote");
+            debug!("loading page {}", page);
+            let mut req = agent.get(&config.url);
             for q in query {
                 req = req.query(&q.0, &q.1);
             }
-            let res = req.query("page", &req_count.to_string()).call()?;
-            let text = res.into_string();
+            match req.query("page", &page.to_string()).call() {
+                Ok(res) => {
+                    let text = res.into_string();
+                    debug!("loaded from xivapi: {:?}", text);
+                    let mut data: self::xivapi::Response = serde_json::from_str(text?.as_str())?;
+                    results.append(&mut data.results);
+                    pages += 1;
+                    match data.pagination.page_next {
+                        Some(next) => page = next,
+                        None => break,
+                    }
+                    attempt = 0;
+                }
+                Err(ureq::Error::Status(status, res)) if XivapiConfig::is_retryable(status) => {
+                    if retries >= config.retry_budget {
+                        warn!(
+                            "giving up on page {} after {} retries (status {})",
+                            page, retries, status
+                        );
+                        return Err(LogMessageRepositoryError::RequestLimit);
+                    }
+                    let delay = config.retry_delay(res.header("Retry-After"), attempt);
+                    debug!(
+                        "page {} returned status {}, retrying in {:?}",
+                        page, status, delay
+                    );
+                    std::thread::sleep(delay);
+                    retries += 1;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(results)
+    }
+
+    #[cfg(feature = "xivapi")]
+    pub fn reload_messages(&mut self) -> Result<()> {
+        let loaded = Self::parse_xivapi(
+            Self::load_xivapi_with_config(&self.query, &self.xivapi_config)?,
+            &self.xivapi_config,
+        );
+        self.set_messages(loaded);
+        Ok(())
+    }
+
+    /// Builds a repository from saved xivapi responses, one per page. Both the snake_case
+    /// columns requested by this crate and xivapi's default PascalCase columns, such as
+    /// `LogMessageTargeted.Text_en`, are accepted.
+    #[cfg(feature = "json")]
+    pub fn from_xivapi_dump<S: AsRef<str>>(pages: &[S]) -> Result<LogMessageRepository> {
+        let mut results = Vec::new();
+        for page in pages {
+            let page = serde_json::from_str(page.as_ref())?;
+            let mut data: self::xivapi::Response =
+                serde_json::from_value(self::xivapi::snake_case_keys(page))?;
+            results.append(&mut data.results);
+        }
+        let xivapi_config = XivapiConfig::default();
+        let (messages, report) = Self::parse_xivapi(results, &xivapi_config);
+        Ok(LogMessageRepository {
+            xivapi_config,
+            report,
+            ..Self::new(messages)
+        })
+    }
+
+    #[cfg(feature = "xivapi-async")]
+    pub async fn from_xivapi_async(api_key: Option<String>) -> Result<LogMessageRepository> {
+        Self::from_xivapi_async_with_config(api_key, XivapiConfig::default()).await
+    }
+
+    #[cfg(feature = "xivapi-async")]
+    pub async fn from_xivapi_async_with_config(
+        api_key: Option<String>,
+        xivapi_config: XivapiConfig,
+    ) -> Result<LogMessageRepository> {
+        let query = Self::prep_xivapi_query(api_key);
+        let (messages, report) = Self::parse_xivapi(
+            Self::load_xivapi_async_with_config(&query, &xivapi_config).await?,
+            &xivapi_config,
+        );
+        Ok(LogMessageRepository {
+            query,
+            xivapi_config,
+            report,
+            ..Self::new(messages)
+        })
+    }
+
+    #[cfg(feature = "xivapi-async")]
+    pub async fn load_xivapi_async(
+        query: &[(String, String)],
+    ) -> Result<Vec<self::xivapi::EmoteData>> {
+        Self::load_xivapi_async_with_config(query, &XivapiConfig::default()).await
+    }
+
+    /// Async equivalent of [LogMessageRepository::load_xivapi_with_config], with the same retry
+    /// behavior.
+    #[cfg(feature = "xivapi-async")]
+    pub async fn load_xivapi_async_with_config(
+        query: &[(String, String)],
+        config: &XivapiConfig,
+    ) -> Result<Vec<self::xivapi::EmoteData>> {
+        let client = reqwest::Client::builder().timeout(config.timeout).build()?;
+        let mut results = Vec::new();
+        let mut page = 1;
+        let mut pages = 0;
+        let mut retries = 0;
+        let mut attempt = 0;
+        loop {
+            if pages >= XIVAPI_REQUEST_LIMIT {
+                tokio::time::sleep(XIVAPI_THROTTLE_DELAY).await;
+            }
+            debug!("loading page {}", page);
+            let res = client
+                .get(&config.url)
+                .query(query)
+                .query(&[("page", page)])
+                .send()
+                .await?;
+            let status = res.status().as_u16();
+            if XivapiConfig::is_retryable(status) {
+                if retries >= config.retry_budget {
+                    warn!(
+                        "giving up on page {} after {} retries (status {})",
+                        page, retries, status
+                    );
+                    return Err(LogMessageRepositoryError::RequestLimit);
+                }
+                let retry_after = res
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok());
+                let delay = config.retry_delay(retry_after, attempt);
+                debug!(
+                    "page {} returned status {}, retrying in {:?}",
+                    page, status, delay
+                );
+                tokio::time::sleep(delay).await;
+                retries += 1;
+                attempt += 1;
+                continue;
+            }
+            let text = res.error_for_status()?.text().await;
             debug!("loaded from xivapi: {:?}", text);
             let mut data: self::xivapi::Response = serde_json::from_str(text?.as_str())?;
             results.append(&mut data.results);
-            if data.pagination.page_next.is_none() {
-                break;
+            pages += 1;
+            match data.pagination.page_next {
+                Some(next) => page = next,
+                None => break,
             }
+            attempt = 0;
         }
 
         Ok(results)
     }
 
-    #[cfg(feature = "xivapi")]
-    pub fn reload_messages(&mut self) -> Result<()> {
-        self.messages = Self::parse_xivapi(Self::load_xivapi(&self.query)?);
+    #[cfg(feature = "xivapi-async")]
+    pub async fn reload_messages_async(&mut self) -> Result<()> {
+        let loaded = Self::parse_xivapi(
+            Self::load_xivapi_async_with_config(&self.query, &self.xivapi_config).await?,
+            &self.xivapi_config,
+        );
+        self.set_messages(loaded);
         Ok(())
     }
 
-    #[cfg(feature = "xivapi")]
+    /// A copy of this repository's settings with a different set of messages.
+    #[cfg(any(feature = "xivapi", feature = "xivapi-async"))]
+    fn with_messages(&self, (messages, report): (MessagesMap, LoadReport)) -> LogMessageRepository {
+        let mut repository = LogMessageRepository {
+            query: self.query.clone(),
+            xivapi_config: self.xivapi_config.clone(),
+            report,
+            language_dumps: self.language_dumps.clone(),
+            ..Self::new(messages)
+        };
+        repository.merge_language_dumps();
+        repository
+    }
+
+    /// The emotes which were skipped or only partially loaded during the last load from xivapi.
+    #[cfg(feature = "json")]
+    pub fn load_report(&self) -> &LoadReport {
+        &self.report
+    }
+
+    #[cfg(any(feature = "xivapi", feature = "xivapi-async"))]
     pub fn set_xivapi_query(&mut self, query: Vec<(String, String)>) {
         self.query = query;
     }
 
+    #[cfg(feature = "json")]
+    pub fn set_xivapi_config(&mut self, config: XivapiConfig) {
+        self.xivapi_config = config;
+    }
+
     pub fn targeted(&self, name: &str, language: Language) -> Result<&str> {
-        self.messages
-            .get(name)
-            .map(|data| match language {
-                Language::En => data.en.targeted.as_str(),
-                Language::Ja => data.ja.targeted.as_str(),
-            })
-            .ok_or(LogMessageRepositoryError::NotFound)
+        self.messages(name)?
+            .messages_in(language)
+            .and_then(|pair| pair.get(MessageKind::Targeted))
+            .ok_or(LogMessageRepositoryError::NoLogMessages)
     }
 
     pub fn untargeted(&self, name: &str, language: Language) -> Result<&str> {
-        self.messages
-            .get(name)
-            .map(|data| match language {
-                Language::En => data.en.untargeted.as_str(),
-                Language::Ja => data.ja.untargeted.as_str(),
-            })
-            .ok_or(LogMessageRepositoryError::NotFound)
+        self.messages(name)?
+            .messages_in(language)
+            .and_then(|pair| pair.get(MessageKind::Untargeted))
+            .ok_or(LogMessageRepositoryError::NoLogMessages)
     }
 
     pub fn messages(&self, name: &str) -> Result<&Arc<EmoteData>> {
         self.messages
-            .get(name)
+            .get(&normalize_command(name))
             .ok_or(LogMessageRepositoryError::NotFound)
     }
 
@@ -243,34 +1046,131 @@ impl LogMessageRepository {
     }
 
     pub fn contains_emote(&self, name: &str) -> bool {
-        self.messages.contains_key(name)
+        self.messages.contains_key(&normalize_command(name))
     }
 
+    /// Every command of every emote as written in [EmoteData::commands], leaving out commands
+    /// which were dropped as [collisions](LogMessageRepository::command_collisions).
     pub fn emote_list(&self) -> impl Iterator<Item = &String> {
-        self.messages.keys()
+        self.emotes().flat_map(|data| self.commands_of(data))
     }
 
+    /// [LogMessageRepository::emote_list], sorted by emote id.
     pub fn emote_list_by_id(&self) -> impl Iterator<Item = &String> {
-        let mut values: Vec<_> = self.messages.iter().collect();
-        values.sort_unstable_by(|(_, v1), (_, v2)| v1.id.cmp(&v2.id));
-        values.into_iter().map(|(k, _)| k)
+        let mut emotes: Vec<_> = self.emotes().collect();
+        emotes.sort_unstable_by_key(|data| data.id);
+        emotes.into_iter().flat_map(|data| self.commands_of(data))
+    }
+
+    fn commands_of<'a>(&'a self, data: &'a EmoteData) -> impl Iterator<Item = &'a String> {
+        data.commands.iter().filter(move |command| {
+            self.messages
+                .get(&normalize_command(command))
+                .is_some_and(|other| other.id == data.id)
+        })
+    }
+
+    /// Commands of different emotes which normalized to the same key when the messages were
+    /// loaded, sorted by command.
+    pub fn command_collisions(&self) -> &[CommandCollision] {
+        &self.collisions
     }
 
     pub fn find_emote_id(&self, name: &str) -> Option<u32> {
         self.messages
-            .iter()
-            .find(|msg| msg.0 == name)
-            .map(|msg| msg.1.id)
+            .get(&normalize_command(name))
+            .map(|data| data.id)
+    }
+
+    pub fn emote_by_id(&self, id: u32) -> Result<&Arc<EmoteData>> {
+        self.index
+            .by_id
+            .get(&id)
+            .ok_or(LogMessageRepositoryError::NotFound)
+    }
+
+    /// Finds an emote by its exact name in the given language, such as "Surprised" for
+    /// [Language::En].
+    pub fn emote_by_name(&self, name: &str, language: Language) -> Result<&Arc<EmoteData>> {
+        self.index
+            .by_name
+            .get(&language)
+            .and_then(|names| names.get(name))
+            .ok_or(LogMessageRepositoryError::NotFound)
     }
 
     pub fn messages_map(&self) -> &MessagesMap {
         &self.messages
     }
+
+    /// Every emote once, regardless of how many commands it has, in no particular order.
+    pub fn emotes(&self) -> impl Iterator<Item = &Arc<EmoteData>> {
+        self.index.by_id.values()
+    }
+
+    /// Every distinct category of the emotes, sorted by id.
+    pub fn categories(&self) -> Vec<&EmoteCategory> {
+        let mut categories: Vec<_> = self
+            .emotes()
+            .filter_map(|data| data.category.as_ref())
+            .collect();
+        categories.sort_unstable_by_key(|category| category.id);
+        categories.dedup_by_key(|category| category.id);
+        categories
+    }
+
+    pub fn emotes_in_category(&self, category_id: u32) -> impl Iterator<Item = &Arc<EmoteData>> {
+        self.emotes().filter(move |data| {
+            data.category
+                .as_ref()
+                .is_some_and(|category| category.id == category_id)
+        })
+    }
+
+    pub fn emotes_with_log_kind(&self, log_kind: u32) -> impl Iterator<Item = &Arc<EmoteData>> {
+        self.emotes()
+            .filter(move |data| data.log_kind == Some(log_kind))
+    }
+
+    /// Emotes which are always available, without needing to be unlocked.
+    pub fn default_emotes(&self) -> impl Iterator<Item = &Arc<EmoteData>> {
+        self.emotes().filter(|data| !data.requires_unlock())
+    }
 }
 
-#[cfg(feature = "xivapi")]
+#[cfg(feature = "json")]
 pub mod xivapi {
     use serde_derive::Deserialize;
+    use serde_json::Value;
+
+    /// Converts the keys of a response with xivapi's default PascalCase columns to the
+    /// snake_case form returned when requesting with `snake_case=1`, ex. `IconID` to `icon_id`.
+    pub fn snake_case_keys(value: Value) -> Value {
+        match value {
+            Value::Object(map) => Value::Object(
+                map.into_iter()
+                    .map(|(key, value)| (snake_case(&key), snake_case_keys(value)))
+                    .collect(),
+            ),
+            Value::Array(values) => Value::Array(values.into_iter().map(snake_case_keys).collect()),
+            value => value,
+        }
+    }
+
+    fn snake_case(key: &str) -> String {
+        let mut snake = String::with_capacity(key.len() + 4);
+        let mut prev = None;
+        for c in key.chars() {
+            if c.is_ascii_uppercase()
+                && prev.is_some_and(|p: char| p.is_ascii_lowercase() || p.is_ascii_digit())
+            {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+            prev = Some(c);
+        }
+        snake
+    }
 
     #[derive(Debug, Clone, Deserialize)]
     pub struct Response {
@@ -289,13 +1189,26 @@ pub mod xivapi {
         pub log_message_untargeted: Option<LogMessageData>,
         pub text_command: Option<TextCommand>,
         pub name: Option<String>,
+        pub name_en: Option<String>,
+        pub name_ja: Option<String>,
         pub id: Option<u32>,
+        pub emote_category: Option<EmoteCategory>,
+        pub icon_id: Option<u32>,
+        pub unlock_link: Option<u32>,
     }
 
     #[derive(Debug, Clone, Deserialize)]
     pub struct LogMessageData {
         pub text_en: String,
         pub text_ja: String,
+        pub log_kind: Option<u32>,
+    }
+
+    #[derive(Debug, Clone, Deserialize)]
+    pub struct EmoteCategory {
+        pub id: Option<u32>,
+        pub name_en: Option<String>,
+        pub name_ja: Option<String>,
     }
 
     #[derive(Debug, Clone, Deserialize)]
@@ -304,26 +1217,91 @@ pub mod xivapi {
         pub alias_ja: Option<String>,
         pub command_en: Option<String>,
         pub command_ja: Option<String>,
+        pub short_alias_en: Option<String>,
+        pub short_alias_ja: Option<String>,
+        pub short_command_en: Option<String>,
+        pub short_command_ja: Option<String>,
+        pub description_en: Option<String>,
+        pub description_ja: Option<String>,
     }
 }
 
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
 #[cfg_attr(feature = "json", derive(Deserialize))]
 pub enum Language {
     En,
     Ja,
+    /// simplified Chinese, from the Chinese client
+    Chs,
+    /// from the Korean client
+    Ko,
     // not yet supported
     // De,
     // Fr
 }
 
+impl Language {
+    /// Every supported language.
+    pub const ALL: [Language; 4] = [Language::En, Language::Ja, Language::Chs, Language::Ko];
+
+    /// The short code for the language, as used in file names and xivapi columns.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::En => "en",
+            Language::Ja => "ja",
+            Language::Chs => "chs",
+            Language::Ko => "ko",
+        }
+    }
+
+    /// The language with the given [Language::code], ignoring case.
+    pub fn from_code(code: &str) -> Option<Language> {
+        Language::ALL
+            .into_iter()
+            .find(|language| language.code().eq_ignore_ascii_case(code))
+    }
+}
+
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "json", derive(Deserialize))]
 #[allow(unused)]
 pub struct LogMessageData {
     pub id: u32,
     pub name: String,
+    #[cfg_attr(feature = "json", serde(default))]
+    pub names: HashMap<Language, String>,
+    pub commands: Vec<String>,
+    #[cfg_attr(feature = "json", serde(default))]
+    pub descriptions: HashMap<Language, String>,
+    #[cfg_attr(feature = "json", serde(default))]
+    pub category: Option<EmoteCategory>,
+    #[cfg_attr(feature = "json", serde(default))]
+    pub icon: Option<u32>,
+    #[cfg_attr(feature = "json", serde(default))]
+    pub unlock_link: Option<u32>,
+    #[cfg_attr(feature = "json", serde(default))]
+    pub log_kind: Option<u32>,
+    #[cfg_attr(feature = "json", serde(default))]
+    pub en: Option<LogMessagePair>,
+    #[cfg_attr(feature = "json", serde(default))]
+    pub ja: Option<LogMessagePair>,
+    #[cfg_attr(feature = "json", serde(default))]
+    pub chs: Option<LogMessagePair>,
+    #[cfg_attr(feature = "json", serde(default))]
+    pub ko: Option<LogMessagePair>,
+}
+
+/// One emote from a dump of a single client language, such as the chs or ko clients which
+/// xivapi does not serve.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "json", derive(Deserialize))]
+#[allow(unused)]
+pub struct LanguageDumpData {
+    pub id: u32,
+    pub name: Option<String>,
+    #[cfg_attr(feature = "json", serde(default))]
     pub commands: Vec<String>,
-    pub en: LogMessagePair,
-    pub ja: LogMessagePair,
+    pub description: Option<String>,
+    pub targeted: Option<String>,
+    pub untargeted: Option<String>,
 }
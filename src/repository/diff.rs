@@ -0,0 +1,112 @@
+use std::{collections::BTreeSet, sync::Arc};
+
+use super::{normalize_command, EmoteData, Language, LogMessageRepository};
+
+/// The differences between two repositories, such as dumps from before and after a game patch.
+/// Emotes are matched by id, and every list is sorted by id.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepositoryDiff {
+    /// emotes only in the newer repository
+    pub added: Vec<Arc<EmoteData>>,
+    /// emotes only in the older repository
+    pub removed: Vec<Arc<EmoteData>>,
+    /// emotes in both repositories whose commands or messages differ
+    pub changed: Vec<EmoteChange>,
+}
+
+impl RepositoryDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmoteChange {
+    pub id: u32,
+    /// the emote's name in the newer repository
+    pub name: String,
+    /// normalized commands only in the newer repository, sorted
+    pub added_commands: Vec<String>,
+    /// normalized commands only in the older repository, sorted
+    pub removed_commands: Vec<String>,
+    pub messages: Vec<MessageChange>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MessageKind {
+    Targeted,
+    Untargeted,
+}
+
+/// A log message which differs between the repositories, where `None` means the emote had no
+/// log messages in that language.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageChange {
+    pub language: Language,
+    pub kind: MessageKind,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+impl LogMessageRepository {
+    /// Compares this repository against a newer one, reporting the emotes, commands and log
+    /// messages which were added, removed or changed.
+    pub fn diff(&self, newer: &LogMessageRepository) -> RepositoryDiff {
+        let mut diff = RepositoryDiff::default();
+        for old in self.emotes() {
+            match newer.index.by_id.get(&old.id) {
+                Some(new) => diff.changed.extend(EmoteChange::new(old, new)),
+                None => diff.removed.push(old.clone()),
+            }
+        }
+        diff.added = newer
+            .emotes()
+            .filter(|new| !self.index.by_id.contains_key(&new.id))
+            .cloned()
+            .collect();
+        diff.added.sort_unstable_by_key(|data| data.id);
+        diff.removed.sort_unstable_by_key(|data| data.id);
+        diff.changed.sort_unstable_by_key(|change| change.id);
+        diff
+    }
+}
+
+impl EmoteChange {
+    /// The changes between two versions of an emote, or `None` if there are none.
+    fn new(old: &EmoteData, new: &EmoteData) -> Option<EmoteChange> {
+        let commands = |data: &EmoteData| -> BTreeSet<String> {
+            data.commands.iter().map(|c| normalize_command(c)).collect()
+        };
+        let (old_commands, new_commands) = (commands(old), commands(new));
+        let messages: Vec<_> = Language::ALL
+            .into_iter()
+            .flat_map(|language| {
+                [MessageKind::Targeted, MessageKind::Untargeted].map(|kind| {
+                    let text = |data: &EmoteData| {
+                        data.messages_in(language)
+                            .and_then(|pair| pair.get(kind))
+                            .map(str::to_string)
+                    };
+                    MessageChange {
+                        language,
+                        kind,
+                        old: text(old),
+                        new: text(new),
+                    }
+                })
+            })
+            .filter(|change| change.old != change.new)
+            .collect();
+        let change = EmoteChange {
+            id: new.id,
+            name: new.name.clone(),
+            added_commands: new_commands.difference(&old_commands).cloned().collect(),
+            removed_commands: old_commands.difference(&new_commands).cloned().collect(),
+            messages,
+        };
+        let unchanged = change.added_commands.is_empty()
+            && change.removed_commands.is_empty()
+            && change.messages.is_empty();
+        (!unchanged).then_some(change)
+    }
+}
@@ -0,0 +1,138 @@
+use std::{collections::HashMap, path::Path};
+
+use crate::sqpack::{
+    excel::{ExcelHeader, ExcelLanguage, ExcelSheet},
+    SqPack,
+};
+
+use super::{
+    sheets::{LanguageSheets, Sheet},
+    Language, LogMessageRepository, Result,
+};
+
+/// Which columns of each sheet hold the data used by the repository, by the names used in
+/// SaintCoinach's csv exports. The indices shift occasionally between game patches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SheetColumns {
+    pub emote: HashMap<String, usize>,
+    pub log_message: HashMap<String, usize>,
+    pub text_command: HashMap<String, usize>,
+    pub emote_category: HashMap<String, usize>,
+}
+
+impl Default for SheetColumns {
+    /// The layout as of patch 6.2, following the column definitions of SaintCoinach
+    /// (`SaintCoinach/Definitions/{Emote,LogMessage,TextCommand,EmoteCategory}.json` in
+    /// <https://github.com/xivapi/SaintCoinach>), where each index is the column's `index`.
+    fn default() -> Self {
+        let columns = |columns: &[(&str, usize)]| {
+            columns
+                .iter()
+                .map(|(name, i)| (name.to_string(), *i))
+                .collect()
+        };
+        SheetColumns {
+            emote: columns(&[
+                ("Name", 0),
+                ("EmoteCategory", 11),
+                ("TextCommand", 18),
+                ("Icon", 19),
+                ("LogMessage{Targeted}", 20),
+                ("LogMessage{Untargeted}", 21),
+                ("UnlockLink", 22),
+            ]),
+            log_message: columns(&[("LogKind", 0), ("Text", 4)]),
+            text_command: columns(&[
+                ("Command", 5),
+                ("ShortCommand", 6),
+                ("Description", 7),
+                ("Alias", 8),
+                ("ShortAlias", 9),
+            ]),
+            emote_category: columns(&[("Name", 0)]),
+        }
+    }
+}
+
+impl Language {
+    fn excel_language(&self) -> ExcelLanguage {
+        match self {
+            Language::En => ExcelLanguage::English,
+            Language::Ja => ExcelLanguage::Japanese,
+            Language::Chs => ExcelLanguage::ChineseSimplified,
+            Language::Ko => ExcelLanguage::Korean,
+        }
+    }
+}
+
+/// Converts a sheet read from sqpack to the text form shared with the csv loader, keeping
+/// only the given columns.
+fn to_sheet(
+    name: &'static str,
+    sheet: ExcelSheet,
+    columns: &HashMap<String, usize>,
+) -> Result<Sheet> {
+    if let Some((column, _)) = columns
+        .iter()
+        .find(|(_, &i)| i >= sheet.header.columns.len())
+    {
+        return Err(Sheet::invalid(name, &format!("missing column {}", column)));
+    }
+    let (names, indices): (Vec<_>, Vec<_>) = columns.iter().unzip();
+    let rows = sheet
+        .rows
+        .into_iter()
+        .map(|(id, values)| {
+            let row = indices.iter().map(|&&i| values[i].to_text()).collect();
+            (id, row)
+        })
+        .collect();
+    Ok(Sheet {
+        name,
+        columns: names
+            .into_iter()
+            .enumerate()
+            .map(|(i, name)| (name.clone(), i))
+            .collect(),
+        rows,
+    })
+}
+
+impl LogMessageRepository {
+    /// Builds a repository from the excel sheets in a game installation's sqpack files, where
+    /// `game_dir` is the `game` folder holding `sqpack`. Every language in [Language::ALL] which
+    /// the installation has is loaded, with SeStrings converted to their macro text form.
+    pub fn from_sqpack(game_dir: impl AsRef<Path>) -> Result<LogMessageRepository> {
+        Self::from_sqpack_with_columns(game_dir, &SheetColumns::default())
+    }
+
+    pub fn from_sqpack_with_columns(
+        game_dir: impl AsRef<Path>,
+        columns: &SheetColumns,
+    ) -> Result<LogMessageRepository> {
+        let sqpack = SqPack::open(game_dir)?;
+        let exd = sqpack.exd();
+        let available = ExcelHeader::read(exd, "Emote")?.languages;
+        let sheets = Language::ALL
+            .into_iter()
+            .filter(|language| available.contains(&language.excel_language()))
+            .map(|language| {
+                let read = |name, columns| {
+                    to_sheet(
+                        name,
+                        ExcelSheet::read(exd, name, language.excel_language())?,
+                        columns,
+                    )
+                };
+                let sheets = LanguageSheets {
+                    emote: read("Emote", &columns.emote)?,
+                    log_message: read("LogMessage", &columns.log_message)?,
+                    text_command: read("TextCommand", &columns.text_command)?,
+                    emote_category: Some(read("EmoteCategory", &columns.emote_category)?),
+                };
+                Ok((language, sheets))
+            })
+            .collect::<Result<_>>()?;
+        Self::from_sheets(sheets)
+    }
+}
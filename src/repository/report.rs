@@ -0,0 +1,51 @@
+/// What happened to the emotes loaded from xivapi which could not be fully added to the
+/// repository.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadReport {
+    /// emotes which were left out of the repository
+    pub skipped: Vec<SkippedEmote>,
+    /// emotes which were added to the repository with some of their data missing
+    pub partial: Vec<PartialEmote>,
+}
+
+impl LoadReport {
+    /// Whether every loaded emote was added to the repository in full.
+    pub fn is_empty(&self) -> bool {
+        self.skipped.is_empty() && self.partial.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedEmote {
+    pub id: Option<u32>,
+    pub name: Option<String>,
+    pub reason: SkipReason,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkipReason {
+    MissingId,
+    MissingName,
+    MissingTextCommand,
+    /// the text command exists, but all of its commands and aliases are empty
+    NoCommands,
+    /// the emote is missing some of its log messages, and motion-only emotes were not
+    /// requested with [XivapiConfig::include_motion_only](super::XivapiConfig::include_motion_only)
+    MissingLogMessages(Vec<MissingField>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartialEmote {
+    pub id: u32,
+    pub name: String,
+    /// the languages missing the messages, which are en and ja for emotes from xivapi and the
+    /// dump's language for emotes from [load_language_dump](super::LogMessageRepository::load_language_dump)
+    pub languages: Vec<super::Language>,
+    pub missing: Vec<MissingField>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MissingField {
+    LogMessageTargeted,
+    LogMessageUntargeted,
+}
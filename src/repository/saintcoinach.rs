@@ -0,0 +1,117 @@
+use std::{collections::HashMap, path::Path};
+
+use csv::ReaderBuilder;
+
+use super::{
+    sheets::{LanguageSheets, Sheet},
+    Language, LogMessageRepository, Result,
+};
+
+/// The contents of the sheets exported by SaintCoinach's `rawexd` command for a single client
+/// language. Link columns in raw exports hold row ids, which is how the sheets are joined.
+#[derive(Debug, Clone, Default)]
+pub struct CsvSheets {
+    /// Emote.csv
+    pub emote: String,
+    /// LogMessage.csv
+    pub log_message: String,
+    /// TextCommand.csv
+    pub text_command: String,
+    /// EmoteCategory.csv, used for category names if present
+    pub emote_category: Option<String>,
+}
+
+impl CsvSheets {
+    /// Reads the sheets from a directory, such as `rawexd/en`.
+    pub fn from_dir(dir: impl AsRef<Path>) -> Result<CsvSheets> {
+        let dir = dir.as_ref();
+        let read = |name: &str| std::fs::read_to_string(dir.join(name));
+        let emote_category = match read("EmoteCategory.csv") {
+            Ok(sheet) => Some(sheet),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        Ok(CsvSheets {
+            emote: read("Emote.csv")?,
+            log_message: read("LogMessage.csv")?,
+            text_command: read("TextCommand.csv")?,
+            emote_category,
+        })
+    }
+}
+
+/// Parses a sheet with SaintCoinach's three header rows (column index, column name and type).
+fn parse_sheet(name: &'static str, data: &str) -> Result<Sheet> {
+    let mut records = ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(data.as_bytes())
+        .into_records();
+    // the first header row is just the column indices
+    let mut headers = records.by_ref().take(3).skip(1);
+    let columns = headers
+        .next()
+        .transpose()?
+        .ok_or_else(|| Sheet::invalid(name, "missing header rows"))?
+        .iter()
+        .enumerate()
+        .map(|(i, column)| (column.to_string(), i))
+        .collect();
+    headers.for_each(drop);
+    let rows = records
+        .map(|record| {
+            let record = record?;
+            let id = record
+                .get(0)
+                .and_then(|id| id.parse().ok())
+                .ok_or_else(|| Sheet::invalid(name, "row without a numeric id"))?;
+            Ok((id, record.iter().map(String::from).collect()))
+        })
+        .collect::<Result<_>>()?;
+    Ok(Sheet {
+        name,
+        columns,
+        rows,
+    })
+}
+
+fn parse_sheets(sheets: &CsvSheets) -> Result<LanguageSheets> {
+    Ok(LanguageSheets {
+        emote: parse_sheet("Emote", &sheets.emote)?,
+        log_message: parse_sheet("LogMessage", &sheets.log_message)?,
+        text_command: parse_sheet("TextCommand", &sheets.text_command)?,
+        emote_category: sheets
+            .emote_category
+            .as_deref()
+            .map(|sheet| parse_sheet("EmoteCategory", sheet))
+            .transpose()?,
+    })
+}
+
+impl LogMessageRepository {
+    /// Builds a repository from SaintCoinach `rawexd` CSV exports, one set of sheets per
+    /// client language. Row ids and links are taken from the first language in
+    /// [Language::ALL] order, and emotes without a name or any commands are left out.
+    /// Emotes without log messages, such as motion-only emotes, are kept with empty pairs.
+    pub fn from_csv(sheets: &HashMap<Language, CsvSheets>) -> Result<LogMessageRepository> {
+        let sheets = Language::ALL
+            .into_iter()
+            .filter_map(|language| Some((language, sheets.get(&language)?)))
+            .map(|(language, sheets)| Ok((language, parse_sheets(sheets)?)))
+            .collect::<Result<Vec<_>>>()?;
+        Self::from_sheets(sheets)
+    }
+
+    /// Builds a repository from a directory of SaintCoinach `rawexd` CSV exports, with the
+    /// sheets for each client language in a subdirectory named by [Language::code], such as
+    /// `en/Emote.csv`. Languages without a subdirectory are skipped.
+    pub fn from_csv_dir(dir: impl AsRef<Path>) -> Result<LogMessageRepository> {
+        let sheets = Language::ALL
+            .into_iter()
+            .map(|language| (language, dir.as_ref().join(language.code())))
+            .filter(|(_, dir)| dir.is_dir())
+            .map(|(language, dir)| Ok((language, CsvSheets::from_dir(dir)?)))
+            .collect::<Result<_>>()?;
+        Self::from_csv(&sheets)
+    }
+}
@@ -0,0 +1,104 @@
+//! Prefix completion and typo-tolerant search over emote commands.
+
+use std::{cmp::Ordering, sync::Arc};
+
+use super::{normalize_command, EmoteData, LogMessageRepository};
+
+/// How a command matched a search query. Variants are ordered from best to worst match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MatchKind {
+    /// the command is exactly the query
+    Exact,
+    /// the command starts with the query
+    Prefix,
+    /// the command, or the start of it, is within a few edits of the query
+    Fuzzy,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchMatch<'a> {
+    pub command: &'a str,
+    pub emote: &'a Arc<EmoteData>,
+    pub kind: MatchKind,
+    /// number of edits (insertions, deletions, substitutions or transpositions) between
+    /// the query and the command, 0 for exact and prefix matches
+    pub distance: usize,
+}
+
+impl Ord for SearchMatch<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.kind
+            .cmp(&other.kind)
+            .then(self.distance.cmp(&other.distance))
+            .then(self.command.len().cmp(&other.command.len()))
+            .then(self.command.cmp(other.command))
+    }
+}
+
+impl PartialOrd for SearchMatch<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The most edits allowed for a query to still fuzzily match, scaled by its length.
+fn max_distance(query: &str) -> usize {
+    (query.trim_start_matches('/').chars().count() / 4).max(1)
+}
+
+impl LogMessageRepository {
+    /// All commands starting with the prefix, in sorted order. Includes commands and aliases
+    /// from every language. The prefix is normalized with [normalize_command].
+    pub fn complete<'a>(&'a self, prefix: &str) -> impl Iterator<Item = &'a str> + 'a {
+        let prefix = normalize_command(prefix);
+        let commands = &self.index.commands;
+        let start = commands.partition_point(|command| *command < prefix);
+        commands[start..]
+            .iter()
+            .take_while(move |command| command.starts_with(&prefix))
+            .map(String::as_str)
+    }
+
+    /// Commands matching the query exactly, by prefix, or within a few typos, ranked from
+    /// best to worst match and truncated to at most limit results. The query is normalized
+    /// with [normalize_command].
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchMatch<'_>> {
+        let query = normalize_command(query);
+        let query = query.as_str();
+        let query_len = query.chars().count();
+        let max_distance = max_distance(query);
+        let mut matches: Vec<_> = self
+            .messages
+            .iter()
+            .filter_map(|(command, emote)| {
+                let (kind, distance) = if command == query {
+                    (MatchKind::Exact, 0)
+                } else if command.starts_with(query) {
+                    (MatchKind::Prefix, 0)
+                } else {
+                    // compare against the start of the command too, so that partially typed
+                    // commands with typos still match
+                    let distance = (query_len.saturating_sub(1)..=query_len + 1)
+                        .map(|len| command.chars().take(len).collect::<String>())
+                        .chain(std::iter::once(command.clone()))
+                        .map(|command| strsim::osa_distance(query, &command))
+                        .min()
+                        .unwrap_or(usize::MAX);
+                    if distance > max_distance {
+                        return None;
+                    }
+                    (MatchKind::Fuzzy, distance)
+                };
+                Some(SearchMatch {
+                    command,
+                    emote,
+                    kind,
+                    distance,
+                })
+            })
+            .collect();
+        matches.sort_unstable();
+        matches.truncate(limit);
+        matches
+    }
+}
@@ -0,0 +1,83 @@
+//! A repository handle that can be shared between threads and reloaded
+//! without blocking readers.
+
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+
+use super::LogMessageRepository;
+#[cfg(any(feature = "xivapi", feature = "xivapi-async"))]
+use super::Result;
+
+/// Cheaply cloneable handle to a [LogMessageRepository].
+///
+/// Readers take a snapshot with [SharedLogMessageRepository::load], which never waits on a reload
+/// in progress. Reloads build a new repository off to the side and swap it in atomically once it
+/// is complete, so a failed reload leaves the current repository in place. Concurrent reloads
+/// each fetch separately, and whichever finishes last is kept.
+#[derive(Debug, Clone)]
+pub struct SharedLogMessageRepository {
+    current: Arc<ArcSwap<LogMessageRepository>>,
+}
+
+impl SharedLogMessageRepository {
+    pub fn new(repository: LogMessageRepository) -> SharedLogMessageRepository {
+        SharedLogMessageRepository {
+            current: Arc::new(ArcSwap::from_pointee(repository)),
+        }
+    }
+
+    /// A snapshot of the current repository, unaffected by later reloads.
+    pub fn load(&self) -> Arc<LogMessageRepository> {
+        self.current.load_full()
+    }
+
+    /// Swaps in a repository built elsewhere, such as from json.
+    pub fn replace(&self, repository: LogMessageRepository) {
+        self.current.store(Arc::new(repository));
+    }
+
+    /// Reloads messages from xivapi on the current thread using the current repository's
+    /// query and config. Readers continue to see the previous messages until this finishes.
+    #[cfg(feature = "xivapi")]
+    pub fn reload(&self) -> Result<()> {
+        let current = self.load();
+        let results =
+            LogMessageRepository::load_xivapi_with_config(&current.query, &current.xivapi_config)?;
+        self.replace(current.with_messages(LogMessageRepository::parse_xivapi(
+            results,
+            &current.xivapi_config,
+        )));
+        Ok(())
+    }
+
+    /// Reloads messages from xivapi on a new thread. The result of the reload can be observed
+    /// by joining the returned handle.
+    #[cfg(feature = "xivapi")]
+    pub fn reload_in_background(&self) -> std::thread::JoinHandle<Result<()>> {
+        let handle = self.clone();
+        std::thread::spawn(move || handle.reload())
+    }
+
+    /// Async equivalent of [SharedLogMessageRepository::reload].
+    #[cfg(feature = "xivapi-async")]
+    pub async fn reload_async(&self) -> Result<()> {
+        let current = self.load();
+        let results = LogMessageRepository::load_xivapi_async_with_config(
+            &current.query,
+            &current.xivapi_config,
+        )
+        .await?;
+        self.replace(current.with_messages(LogMessageRepository::parse_xivapi(
+            results,
+            &current.xivapi_config,
+        )));
+        Ok(())
+    }
+}
+
+impl From<LogMessageRepository> for SharedLogMessageRepository {
+    fn from(repository: LogMessageRepository) -> Self {
+        SharedLogMessageRepository::new(repository)
+    }
+}
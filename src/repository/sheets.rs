@@ -0,0 +1,171 @@
+use std::{collections::HashMap, sync::Arc};
+
+use tracing::*;
+
+use super::{
+    EmoteCategory, EmoteData, Language, LogMessagePair, LogMessageRepository,
+    LogMessageRepositoryError, MessagesMap, Result,
+};
+
+/// A sheet of game data in the text form used by SaintCoinach's csv exports, with its rows
+/// keyed by row id and its columns by name.
+pub(super) struct Sheet {
+    pub(super) name: &'static str,
+    pub(super) columns: HashMap<String, usize>,
+    pub(super) rows: HashMap<u32, Vec<String>>,
+}
+
+impl Sheet {
+    pub(super) fn invalid(sheet: &'static str, reason: &str) -> LogMessageRepositoryError {
+        LogMessageRepositoryError::InvalidSheet {
+            sheet,
+            reason: reason.to_string(),
+        }
+    }
+
+    fn column(&self, column: &str) -> Result<usize> {
+        self.columns
+            .get(column)
+            .copied()
+            .ok_or_else(|| Self::invalid(self.name, &format!("missing column {}", column)))
+    }
+
+    /// The non-empty value of a column in a row, if both exist.
+    fn get(&self, id: u32, column: &str) -> Result<Option<&str>> {
+        let column = self.column(column)?;
+        Ok(self
+            .rows
+            .get(&id)
+            .and_then(|row| row.get(column))
+            .map(String::as_str)
+            .filter(|value| !value.is_empty()))
+    }
+
+    /// The value of a link column, where 0 means no link.
+    fn get_id(&self, id: u32, column: &str) -> Result<Option<u32>> {
+        Ok(self
+            .get(id, column)?
+            .and_then(|value| value.parse().ok())
+            .filter(|&value| value != 0))
+    }
+}
+
+pub(super) struct LanguageSheets {
+    pub(super) emote: Sheet,
+    pub(super) log_message: Sheet,
+    pub(super) text_command: Sheet,
+    pub(super) emote_category: Option<Sheet>,
+}
+
+impl LanguageSheets {
+    fn log_messages(&self, id: u32) -> Result<(Option<LogMessagePair>, Option<u32>)> {
+        let targeted = self.emote.get_id(id, "LogMessage{Targeted}")?;
+        let untargeted = self.emote.get_id(id, "LogMessage{Untargeted}")?;
+        let text = |message: Option<u32>| match message {
+            Some(message) => self.log_message.get(message, "Text"),
+            None => Ok(None),
+        };
+        let pair = LogMessagePair::new(
+            text(targeted)?.map(str::to_string),
+            text(untargeted)?.map(str::to_string),
+        );
+        let log_kind = match targeted.or(untargeted) {
+            Some(message) => self.log_message.get_id(message, "LogKind")?,
+            None => None,
+        };
+        Ok((pair, log_kind))
+    }
+}
+
+impl LogMessageRepository {
+    /// Joins the sheets of each language by row id into [EmoteData]. Row ids and links are
+    /// taken from the first language, and emotes without a name or any commands are left out.
+    pub(super) fn from_sheets(
+        sheets: Vec<(Language, LanguageSheets)>,
+    ) -> Result<LogMessageRepository> {
+        let (_, base) = sheets
+            .first()
+            .ok_or_else(|| Sheet::invalid("Emote", "no sheets for any language"))?;
+        let mut ids: Vec<_> = base.emote.rows.keys().copied().collect();
+        ids.sort_unstable();
+        let mut messages = MessagesMap::new();
+        for id in ids {
+            let mut data = EmoteData {
+                id,
+                name: String::new(),
+                names: HashMap::new(),
+                commands: Vec::new(),
+                descriptions: HashMap::new(),
+                category: None,
+                icon: base.emote.get_id(id, "Icon")?,
+                unlock_link: base
+                    .emote
+                    .get(id, "UnlockLink")?
+                    .and_then(|link| link.parse().ok()),
+                log_kind: None,
+                en: None,
+                ja: None,
+                chs: None,
+                ko: None,
+            };
+            let text_command = base.emote.get_id(id, "TextCommand")?;
+            let category = base.emote.get_id(id, "EmoteCategory")?;
+            for (language, sheets) in &sheets {
+                if let Some(name) = sheets.emote.get(id, "Name")? {
+                    data.names.insert(*language, name.to_string());
+                }
+                if let Some(text_command) = text_command {
+                    for column in ["Command", "ShortCommand", "Alias", "ShortAlias"] {
+                        if let Some(command) = sheets.text_command.get(text_command, column)? {
+                            if !data.commands.iter().any(|c| c == command) {
+                                data.commands.push(command.to_string());
+                            }
+                        }
+                    }
+                    if let Some(description) =
+                        sheets.text_command.get(text_command, "Description")?
+                    {
+                        data.descriptions.insert(*language, description.to_string());
+                    }
+                }
+                let (pair, log_kind) = sheets.log_messages(id)?;
+                data.log_kind = data.log_kind.or(log_kind);
+                match language {
+                    Language::En => data.en = pair,
+                    Language::Ja => data.ja = pair,
+                    Language::Chs => data.chs = pair,
+                    Language::Ko => data.ko = pair,
+                }
+                if let (Some(category), Some(category_sheet)) = (category, &sheets.emote_category) {
+                    let category = data.category.get_or_insert_with(|| EmoteCategory {
+                        id: category,
+                        names: HashMap::new(),
+                    });
+                    if let Some(name) = category_sheet.get(category.id, "Name")? {
+                        category.names.insert(*language, name.to_string());
+                    }
+                }
+            }
+            data.category = data.category.or(category.map(|id| EmoteCategory {
+                id,
+                names: HashMap::new(),
+            }));
+            match sheets
+                .iter()
+                .find_map(|(language, _)| data.names.get(language))
+            {
+                Some(name) if !data.commands.is_empty() => data.name = name.clone(),
+                _ => {
+                    trace!("skipping emote {} without a name or commands", id);
+                    continue;
+                }
+            }
+            let data = Arc::new(data);
+            for command in &data.commands {
+                trace!("{} => {}", command, data.name);
+                messages.insert(command.clone(), data.clone());
+            }
+        }
+        Ok(Self::new(messages))
+    }
+}
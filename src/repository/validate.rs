@@ -0,0 +1,199 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use crate::log_message::{
+    ast::types::{EmoteTextProcessError, FuncName, TagName},
+    parser::extract_condition_texts,
+    EmoteTextError, EmoteTextErrorKind,
+};
+
+use super::{Language, LogMessageRepository, MessageKind};
+
+/// The log messages in a repository which could not be processed, grouped by the kind of error.
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    /// the number of log messages that were checked
+    pub checked: usize,
+    pub failures: BTreeMap<EmoteTextErrorKind, Vec<ValidationFailure>>,
+}
+
+impl ValidationReport {
+    /// Whether every log message could be processed.
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    pub fn failure_count(&self) -> usize {
+        self.failures.values().map(Vec::len).sum()
+    }
+
+    /// The failures grouped by language and then by the [Construct] responsible for them.
+    pub fn by_construct(&self) -> BTreeMap<Language, BTreeMap<Construct, Vec<&ValidationFailure>>> {
+        let mut grouped: BTreeMap<_, BTreeMap<_, Vec<_>>> = BTreeMap::new();
+        for failure in self.failures.values().flatten() {
+            grouped
+                .entry(failure.language)
+                .or_default()
+                .entry(failure.construct())
+                .or_default()
+                .push(failure);
+        }
+        grouped
+    }
+}
+
+#[derive(Debug)]
+pub struct ValidationFailure {
+    pub id: u32,
+    pub name: String,
+    pub language: Language,
+    pub kind: MessageKind,
+    /// the log message which failed to process
+    pub message: String,
+    pub error: EmoteTextError,
+}
+
+impl ValidationFailure {
+    /// The construct of the log message which caused the failure.
+    pub fn construct(&self) -> Construct {
+        match &self.error {
+            EmoteTextError::ParseError(_)
+            | EmoteTextError::AstError(_)
+            | EmoteTextError::MessageParseError => {
+                unknown_name(&self.message).unwrap_or(Construct::Syntax)
+            }
+            EmoteTextError::ProcessError(e) => match e {
+                EmoteTextProcessError::DanglingFunction { name }
+                | EmoteTextProcessError::InvalidFunc { name, .. }
+                | EmoteTextProcessError::UnexpectedFuncReturn { name, .. } => {
+                    Construct::Function(*name)
+                }
+                EmoteTextProcessError::InvalidTag { name, .. }
+                | EmoteTextProcessError::UnexpectedTagReturn { name, .. } => Construct::Tag(*name),
+                EmoteTextProcessError::UnexpectedClickable { .. } => {
+                    Construct::Tag(TagName::Clickable)
+                }
+                EmoteTextProcessError::ConditionError(e) => {
+                    Construct::Condition(e.origin().to_string())
+                }
+                EmoteTextProcessError::DynamicTextError(e) => {
+                    Construct::DynamicText(e.origin().to_string())
+                }
+                EmoteTextProcessError::UnexpectedObj { .. }
+                | EmoteTextProcessError::UnexpectedNum { .. } => {
+                    Construct::Other(self.error.kind())
+                }
+            },
+            EmoteTextError::DecodeError(_) => Construct::Other(self.error.kind()),
+        }
+    }
+}
+
+/// The part of a log message responsible for a [ValidationFailure], for grouping failures that
+/// would be fixed by the same change to the parser.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Construct {
+    /// a tag whose name is not a [TagName], which fails to parse
+    UnknownTag(String),
+    /// a function whose name is not a [FuncName], which fails to parse
+    UnknownFunction(String),
+    /// a message which fails to parse for another reason
+    Syntax,
+    /// a known tag used with unsupported parameters
+    Tag(TagName),
+    /// a known function used with unsupported parameters
+    Function(FuncName),
+    /// the macro text of an if-else condition which is not a known
+    /// [Condition](crate::log_message::condition::Condition)
+    Condition(String),
+    /// the macro text of a function or tag which is not a known
+    /// [DynamicText](crate::log_message::condition::DynamicText)
+    DynamicText(String),
+    Other(EmoteTextErrorKind),
+}
+
+impl fmt::Display for Construct {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Construct::UnknownTag(name) => write!(f, "unknown tag {}", name),
+            Construct::UnknownFunction(name) => write!(f, "unknown function {}", name),
+            Construct::Syntax => write!(f, "invalid syntax"),
+            Construct::Tag(name) => write!(f, "unsupported use of tag {:?}", name),
+            Construct::Function(name) => write!(f, "unsupported use of function {:?}", name),
+            Construct::Condition(text) => write!(f, "unknown condition {}", text),
+            Construct::DynamicText(text) => write!(f, "unknown dynamic text {}", text),
+            Construct::Other(kind) => write!(f, "{:?}", kind),
+        }
+    }
+}
+
+/// Finds the first tag or function name in the macro text which the parser does not know.
+/// Names are assumed to start with an uppercase letter, to skip over parentheses in text.
+fn unknown_name(message: &str) -> Option<Construct> {
+    let bytes = message.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        let is_tag = bytes[i] == b'<';
+        let start = if is_tag { i + 1 } else { i };
+        let len = bytes[start..]
+            .iter()
+            .take_while(|b| b.is_ascii_alphanumeric())
+            .count();
+        // empty when not at a char boundary, in text outside of ascii
+        let name = message.get(start..start + len).unwrap_or_default();
+        let starts_name = (is_tag || i == 0 || !bytes[i - 1].is_ascii_alphanumeric())
+            && name.starts_with(|c: char| c.is_ascii_uppercase());
+        if starts_name {
+            let next = bytes.get(start + len).copied();
+            if is_tag
+                && !matches!(name, "If" | "Else")
+                && matches!(next, Some(b'(' | b'/' | b'>'))
+                && TagName::from_str(name).is_err()
+            {
+                return Some(Construct::UnknownTag(name.to_string()));
+            }
+            if !is_tag && next == Some(b'(') && FuncName::from_str(name).is_err() {
+                return Some(Construct::UnknownFunction(name.to_string()));
+            }
+        }
+        i = start + len.max(if is_tag { 0 } else { 1 });
+    }
+    None
+}
+
+impl LogMessageRepository {
+    /// Runs [extract_condition_texts] over every log message of every emote, in each language
+    /// and for both targeted and untargeted messages. Emotes without log messages are skipped.
+    pub fn validate(&self) -> ValidationReport {
+        let mut emotes: Vec<_> = self.emotes().collect();
+        emotes.sort_unstable_by_key(|data| data.id);
+        let mut report = ValidationReport::default();
+        for data in emotes {
+            for language in Language::ALL {
+                let Some(pair) = data.messages_in(language) else {
+                    continue;
+                };
+                for kind in [MessageKind::Targeted, MessageKind::Untargeted] {
+                    let Some(message) = pair.get(kind) else {
+                        continue;
+                    };
+                    report.checked += 1;
+                    if let Err(error) = extract_condition_texts(message) {
+                        report
+                            .failures
+                            .entry(error.kind())
+                            .or_default()
+                            .push(ValidationFailure {
+                                id: data.id,
+                                name: data.name.clone(),
+                                language,
+                                kind,
+                                message: message.to_string(),
+                                error,
+                            });
+                    }
+                }
+            }
+        }
+        report
+    }
+}
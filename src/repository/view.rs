@@ -0,0 +1,52 @@
+use std::collections::BTreeMap;
+
+use serde_derive::Serialize;
+
+use super::{EmoteData, Language};
+
+/// A serializable view of an emote for apis and bindings, with its names and log messages keyed
+/// by language code, ex. `{ "id": 1, "name": "Surprised", "names": { "en": "Surprised" },
+/// "commands": ["/surprised"], "messages": { "en": { "targeted": "...", "untargeted": "..." } } }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EmoteView<'a> {
+    pub id: u32,
+    pub name: &'a str,
+    pub names: BTreeMap<&'static str, &'a str>,
+    pub commands: &'a [String],
+    /// log messages by language code, leaving out languages without them
+    pub messages: BTreeMap<&'static str, MessagesView<'a>>,
+}
+
+/// The log messages of an emote in one language, either of which may be missing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MessagesView<'a> {
+    pub targeted: Option<&'a str>,
+    pub untargeted: Option<&'a str>,
+}
+
+impl<'a> From<&'a EmoteData> for EmoteView<'a> {
+    fn from(data: &'a EmoteData) -> EmoteView<'a> {
+        EmoteView {
+            id: data.id,
+            name: &data.name,
+            names: Language::ALL
+                .into_iter()
+                .filter_map(|language| Some((language.code(), data.name_in(language)?)))
+                .collect(),
+            commands: &data.commands,
+            messages: Language::ALL
+                .into_iter()
+                .filter_map(|language| {
+                    let pair = data.messages_in(language)?;
+                    Some((
+                        language.code(),
+                        MessagesView {
+                            targeted: pair.targeted.as_deref(),
+                            untargeted: pair.untargeted.as_deref(),
+                        },
+                    ))
+                })
+                .collect(),
+        }
+    }
+}
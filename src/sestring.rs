@@ -0,0 +1,467 @@
+//! Decoding and encoding of SeString, the binary string format used by the game's data files
+//! and chat. Macros such as conditionals and player names are embedded in the text as payloads,
+//! which are decoded to the same macro text form that xivapi and SaintCoinach produce, ex.
+//! `<If(PlayerParameter(7))>...<Else/>...</If>`. Strings can also be encoded back to bytes,
+//! including the link payloads around player names that the game writes in chat, see
+//! [SeString::player_name].
+
+use std::fmt::Write;
+
+use thiserror::Error;
+
+const PAYLOAD_START: u8 = 0x02;
+const PAYLOAD_END: u8 = 0x03;
+
+/// The first argument of link payloads, marking a link to a player.
+const LINK_PLAYER: u32 = 0x00;
+/// The first argument of link payloads, marking the end of the linked text.
+const LINK_TERMINATOR: u32 = 0xCE;
+
+/// Codes of the macros which appear in emote messages and command descriptions.
+pub mod code {
+    pub const IF: u8 = 0x08;
+    pub const SWITCH: u8 = 0x09;
+    pub const NEW_LINE: u8 = 0x10;
+    /// also used for links, such as to players in chat
+    pub const CLICKABLE: u8 = 0x27;
+    pub const SHEET: u8 = 0x28;
+    pub const SHEET_JA: u8 = 0x30;
+    pub const SHEET_EN: u8 = 0x31;
+    pub const SHEET_DE: u8 = 0x32;
+    pub const SHEET_FR: u8 = 0x33;
+    pub const UI_FOREGROUND: u8 = 0x48;
+    pub const UI_GLOW: u8 = 0x49;
+}
+
+/// Codes of the expressions used as macro arguments.
+pub mod expr {
+    pub const GREATER_THAN_OR_EQUAL: u8 = 0xE0;
+    pub const GREATER_THAN: u8 = 0xE1;
+    pub const LESS_THAN_OR_EQUAL: u8 = 0xE2;
+    pub const LESS_THAN: u8 = 0xE3;
+    pub const EQUAL: u8 = 0xE4;
+    pub const NOT_EQUAL: u8 = 0xE5;
+    pub const INTEGER_PARAMETER: u8 = 0xE8;
+    pub const PLAYER_PARAMETER: u8 = 0xE9;
+    pub const STRING_PARAMETER: u8 = 0xEA;
+    pub const OBJECT_PARAMETER: u8 = 0xEB;
+    /// the previous color, used to end a colored section
+    pub const STACK_COLOR: u8 = 0xEC;
+    pub const STRING: u8 = 0xFF;
+}
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum SeStringError {
+    #[error("SeString ended in the middle of a payload")]
+    UnexpectedEnd,
+    #[error("Invalid integer marker ({0:#04x})")]
+    InvalidInteger(u8),
+    #[error("Unknown expression ({0:#04x})")]
+    UnknownExpression(u8),
+    #[error("Payload was not terminated")]
+    MissingPayloadEnd,
+    #[error("Text was not valid utf-8")]
+    InvalidUtf8(#[from] std::str::Utf8Error),
+}
+
+pub type Result<T> = std::result::Result<T, SeStringError>;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SeString(pub Vec<SeStringPart>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SeStringPart {
+    Text(String),
+    Macro(Macro),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Macro {
+    pub code: u8,
+    pub args: Vec<Expression>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expression {
+    Integer(u32),
+    /// an expression without arguments, such as the current hour (0xD8 to 0xDF) or the
+    /// previous color (0xEC)
+    Placeholder(u8),
+    /// a comparison of two expressions (0xE0 to 0xE5)
+    Binary(u8, Box<Expression>, Box<Expression>),
+    /// a lookup of a contextual value, such as a player's name (0xE8 to 0xEB)
+    Parameter(u8, Box<Expression>),
+    String(SeString),
+}
+
+/// Decodes an integer in the packed form used by SeString expressions: single bytes below 0xD0
+/// hold the value plus one, while markers from 0xF0 to 0xFE flag which of the following bytes
+/// (most significant first) make up the value.
+pub fn decode_integer(bytes: &[u8]) -> Option<u32> {
+    let mut reader = Reader { bytes, pos: 0 };
+    let value = reader.integer().ok()?;
+    (reader.pos == bytes.len()).then_some(value)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn is_empty(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    fn byte(&mut self) -> Result<u8> {
+        let b = *self
+            .bytes
+            .get(self.pos)
+            .ok_or(SeStringError::UnexpectedEnd)?;
+        self.pos += 1;
+        Ok(b)
+    }
+
+    fn slice(&mut self, len: usize) -> Result<&'a [u8]> {
+        let slice = self
+            .bytes
+            .get(self.pos..self.pos + len)
+            .ok_or(SeStringError::UnexpectedEnd)?;
+        self.pos += len;
+        Ok(slice)
+    }
+
+    fn integer(&mut self) -> Result<u32> {
+        match self.byte()? {
+            b @ 0x01..=0xCF => Ok(u32::from(b) - 1),
+            marker @ 0xF0..=0xFE => {
+                let flags = marker - 0xEF;
+                let mut value = 0;
+                for shift in [24, 16, 8, 0] {
+                    if flags & (1 << (shift / 8)) != 0 {
+                        value |= u32::from(self.byte()?) << shift;
+                    }
+                }
+                Ok(value)
+            }
+            b => Err(SeStringError::InvalidInteger(b)),
+        }
+    }
+
+    fn expression(&mut self) -> Result<Expression> {
+        let b = self
+            .bytes
+            .get(self.pos)
+            .ok_or(SeStringError::UnexpectedEnd)?;
+        Ok(match *b {
+            0x01..=0xCF | 0xF0..=0xFE => Expression::Integer(self.integer()?),
+            b @ (0xD8..=0xDF | expr::STACK_COLOR) => {
+                self.pos += 1;
+                Expression::Placeholder(b)
+            }
+            b @ 0xE0..=0xE5 => {
+                self.pos += 1;
+                let left = self.expression()?;
+                let right = self.expression()?;
+                Expression::Binary(b, Box::new(left), Box::new(right))
+            }
+            b @ 0xE8..=0xEB => {
+                self.pos += 1;
+                Expression::Parameter(b, Box::new(self.expression()?))
+            }
+            expr::STRING => {
+                self.pos += 1;
+                let len = self.integer()? as usize;
+                Expression::String(SeString::decode(self.slice(len)?)?)
+            }
+            b => return Err(SeStringError::UnknownExpression(b)),
+        })
+    }
+}
+
+impl SeString {
+    /// Parses the raw bytes of an SeString, such as a string column of an EXD sheet.
+    pub fn decode(bytes: &[u8]) -> Result<SeString> {
+        let mut reader = Reader { bytes, pos: 0 };
+        let mut parts = Vec::new();
+        while !reader.is_empty() {
+            let start = reader.pos;
+            while !reader.is_empty() && reader.bytes[reader.pos] != PAYLOAD_START {
+                reader.pos += 1;
+            }
+            if reader.pos > start {
+                let text = std::str::from_utf8(&reader.bytes[start..reader.pos])?;
+                parts.push(SeStringPart::Text(text.to_string()));
+                continue;
+            }
+            reader.pos += 1;
+            let code = reader.byte()?;
+            let len = reader.integer()? as usize;
+            let mut body = Reader {
+                bytes: reader.slice(len)?,
+                pos: 0,
+            };
+            if reader.byte()? != PAYLOAD_END {
+                return Err(SeStringError::MissingPayloadEnd);
+            }
+            let mut args = Vec::new();
+            while !body.is_empty() {
+                args.push(body.expression()?);
+            }
+            parts.push(SeStringPart::Macro(Macro { code, args }));
+        }
+        Ok(SeString(parts))
+    }
+
+    /// The macro text form of the string, as accepted by
+    /// [process_log_message](crate::log_message::process_log_message).
+    pub fn to_macro_text(&self) -> String {
+        let mut text = String::new();
+        self.write_macro_text(&mut text);
+        text
+    }
+
+    fn write_macro_text(&self, out: &mut String) {
+        for part in &self.0 {
+            match part {
+                SeStringPart::Text(text) => out.push_str(text),
+                SeStringPart::Macro(m) => m.write_macro_text(out),
+            }
+        }
+    }
+}
+
+/// Decodes the raw bytes of an SeString straight to its macro text form.
+pub fn to_macro_text(bytes: &[u8]) -> Result<String> {
+    Ok(SeString::decode(bytes)?.to_macro_text())
+}
+
+fn macro_name(code: u8) -> Option<&'static str> {
+    Some(match code {
+        code::IF => "If",
+        code::SWITCH => "Switch",
+        code::NEW_LINE => "br",
+        code::CLICKABLE => "Clickable",
+        code::SHEET => "Sheet",
+        code::SHEET_JA => "SheetJa",
+        code::SHEET_EN => "SheetEn",
+        code::SHEET_DE => "SheetDe",
+        code::SHEET_FR => "SheetFr",
+        code::UI_FOREGROUND => "UIForeground",
+        code::UI_GLOW => "UIGlow",
+        _ => return None,
+    })
+}
+
+fn expression_name(code: u8) -> &'static str {
+    match code {
+        0xD8 => "Millisecond",
+        0xD9 => "Second",
+        0xDA => "Minute",
+        0xDB => "Hour",
+        0xDC => "Day",
+        0xDD => "Weekday",
+        0xDE => "Month",
+        0xDF => "Year",
+        expr::GREATER_THAN_OR_EQUAL => "GreaterThanOrEqual",
+        expr::GREATER_THAN => "GreaterThan",
+        expr::LESS_THAN_OR_EQUAL => "LessThanOrEqual",
+        expr::LESS_THAN => "LessThan",
+        expr::EQUAL => "Equal",
+        expr::NOT_EQUAL => "NotEqual",
+        expr::INTEGER_PARAMETER => "IntegerParameter",
+        expr::PLAYER_PARAMETER => "PlayerParameter",
+        expr::STRING_PARAMETER => "StringParameter",
+        expr::OBJECT_PARAMETER => "ObjectParameter",
+        expr::STACK_COLOR => "StackColor",
+        _ => "Unknown",
+    }
+}
+
+impl Macro {
+    fn write_macro_text(&self, out: &mut String) {
+        let name = macro_name(self.code);
+        match (self.code, &self.args[..]) {
+            (code::IF, [condition, then, otherwise]) => {
+                out.push_str("<If(");
+                condition.write_macro_text(out);
+                out.push_str(")>");
+                then.write_macro_text(out);
+                out.push_str("<Else/>");
+                otherwise.write_macro_text(out);
+                out.push_str("</If>");
+            }
+            (code::SWITCH, [condition, cases @ ..]) => {
+                out.push_str("<Switch(");
+                condition.write_macro_text(out);
+                out.push_str(")>");
+                for (i, case) in cases.iter().enumerate() {
+                    let _ = write!(out, "<Case({})>", i + 1);
+                    case.write_macro_text(out);
+                    out.push_str("</Case>");
+                }
+                out.push_str("</Switch>");
+            }
+            (code::NEW_LINE, []) => out.push_str("<br>"),
+            // colors are written as the hex of their raw expression bytes
+            (code::UI_FOREGROUND | code::UI_GLOW, [color]) => {
+                let name = name.unwrap_or_default();
+                let bytes = match color {
+                    Expression::Integer(color) => encode_integer(*color),
+                    Expression::Placeholder(code) => vec![*code],
+                    _ => vec![],
+                };
+                let _ = write!(out, "<{}>", name);
+                for b in bytes {
+                    let _ = write!(out, "{:02X}", b);
+                }
+                let _ = write!(out, "</{}>", name);
+            }
+            (_, args) => {
+                match name {
+                    Some(name) => {
+                        let _ = write!(out, "<{}", name);
+                    }
+                    None => {
+                        let _ = write!(out, "<Macro{:02X}", self.code);
+                    }
+                }
+                if !args.is_empty() {
+                    out.push('(');
+                    write_args(args, out);
+                    out.push(')');
+                }
+                out.push_str("/>");
+            }
+        }
+    }
+}
+
+fn write_args(args: &[Expression], out: &mut String) {
+    for (i, arg) in args.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        arg.write_macro_text(out);
+    }
+}
+
+impl Expression {
+    fn write_macro_text(&self, out: &mut String) {
+        match self {
+            Expression::Integer(value) => {
+                let _ = write!(out, "{}", value);
+            }
+            Expression::Placeholder(code) => out.push_str(expression_name(*code)),
+            Expression::Binary(code, left, right) => {
+                let _ = write!(out, "{}(", expression_name(*code));
+                left.write_macro_text(out);
+                out.push(',');
+                right.write_macro_text(out);
+                out.push(')');
+            }
+            Expression::Parameter(code, index) => {
+                let _ = write!(out, "{}(", expression_name(*code));
+                index.write_macro_text(out);
+                out.push(')');
+            }
+            Expression::String(string) => string.write_macro_text(out),
+        }
+    }
+}
+
+/// Encodes an integer in the packed form read by [decode_integer], using the fewest bytes.
+pub fn encode_integer(value: u32) -> Vec<u8> {
+    if value < 0xCF {
+        return vec![value as u8 + 1];
+    }
+    let mut flags = 0;
+    let mut bytes = vec![0];
+    for shift in [24, 16, 8, 0] {
+        let b = (value >> shift) as u8;
+        if b != 0 {
+            flags |= 1 << (shift / 8);
+            bytes.push(b);
+        }
+    }
+    bytes[0] = 0xEF + flags;
+    bytes
+}
+
+impl SeString {
+    /// Encodes the string to its raw bytes, the inverse of [SeString::decode].
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.write_bytes(&mut bytes);
+        bytes
+    }
+
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        for part in &self.0 {
+            match part {
+                SeStringPart::Text(text) => out.extend_from_slice(text.as_bytes()),
+                SeStringPart::Macro(m) => m.write_bytes(out),
+            }
+        }
+    }
+
+    /// A player's name as the game writes it in chat: a link to the player on their home world
+    /// (a row of the World sheet), the name itself, then the end of the link.
+    pub fn player_name(name: &str, world: u32) -> SeString {
+        let link = |kind, world, name: &str| Macro {
+            code: code::CLICKABLE,
+            args: vec![
+                Expression::Integer(kind),
+                Expression::Integer(0),
+                Expression::Integer(world),
+                Expression::Integer(0),
+                Expression::String(match name {
+                    "" => SeString::default(),
+                    name => SeString(vec![SeStringPart::Text(name.to_string())]),
+                }),
+            ],
+        };
+        SeString(vec![
+            SeStringPart::Macro(link(LINK_PLAYER, world, name)),
+            SeStringPart::Text(name.to_string()),
+            SeStringPart::Macro(link(LINK_TERMINATOR, 0, "")),
+        ])
+    }
+}
+
+impl Macro {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        let mut args = Vec::new();
+        for arg in &self.args {
+            arg.write_bytes(&mut args);
+        }
+        out.extend([PAYLOAD_START, self.code]);
+        out.extend(encode_integer(args.len() as u32));
+        out.extend(args);
+        out.push(PAYLOAD_END);
+    }
+}
+
+impl Expression {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        match self {
+            Expression::Integer(value) => out.extend(encode_integer(*value)),
+            Expression::Placeholder(code) => out.push(*code),
+            Expression::Binary(code, left, right) => {
+                out.push(*code);
+                left.write_bytes(out);
+                right.write_bytes(out);
+            }
+            Expression::Parameter(code, index) => {
+                out.push(*code);
+                index.write_bytes(out);
+            }
+            Expression::String(string) => {
+                let bytes = string.encode();
+                out.push(expr::STRING);
+                out.extend(encode_integer(bytes.len() as u32));
+                out.extend(bytes);
+            }
+        }
+    }
+}
@@ -0,0 +1,224 @@
+//! Reading files from the game's SqPack archives, which are made up of `.index` files mapping
+//! path hashes to locations in `.dat` files.
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use flate2::read::DeflateDecoder;
+use thiserror::Error;
+use tracing::*;
+
+use crate::sestring::SeStringError;
+
+pub mod excel;
+
+/// Blocks with this compressed size are stored uncompressed.
+const UNCOMPRESSED_BLOCK: u32 = 32000;
+/// The most a single block holds once decompressed.
+const MAX_BLOCK_SIZE: u64 = 16000;
+const STANDARD_FILE: u32 = 2;
+
+#[derive(Debug, Error)]
+pub enum SqPackError {
+    #[error("Io error while reading game files")]
+    Io(#[from] std::io::Error),
+    #[error("File not found in sqpack ({0})")]
+    FileNotFound(String),
+    #[error("Invalid {file} ({reason})")]
+    InvalidFile { file: String, reason: String },
+    #[error("Invalid SeString in {sheet} row {row}")]
+    SeString {
+        sheet: String,
+        row: u32,
+        #[source]
+        source: SeStringError,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, SqPackError>;
+
+/// The hash of a path segment used as a key in sqpack indexes, which is a crc32 of the
+/// lowercased segment without the final inversion.
+pub fn path_hash(segment: &str) -> u32 {
+    !crc32(segment.to_lowercase().as_bytes())
+}
+
+/// The key of a file in an index, combining the hashes of its folder and file name.
+pub fn index_key(path: &str) -> u64 {
+    let (folder, file) = path.rsplit_once('/').unwrap_or(("", path));
+    (u64::from(path_hash(folder)) << 32) | u64::from(path_hash(file))
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &b in bytes {
+        crc ^= u32::from(b);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// The location of a file within a category's `.dat` files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Location {
+    data_file: u32,
+    offset: u64,
+}
+
+/// A single sqpack category, such as `0a0000.win32` which holds the excel sheets.
+#[derive(Debug)]
+pub struct Category {
+    /// the index path without its extension
+    base: PathBuf,
+    entries: HashMap<u64, Location>,
+}
+
+impl Category {
+    /// Opens a category by its index file, ex. `sqpack/ffxiv/0a0000.win32.index`.
+    pub fn open(index: impl AsRef<Path>) -> Result<Category> {
+        let index = index.as_ref();
+        let data = std::fs::read(index)?;
+        let invalid = |reason: &str| SqPackError::InvalidFile {
+            file: index.display().to_string(),
+            reason: reason.to_string(),
+        };
+        if !data.starts_with(b"SqPack") {
+            return Err(invalid("missing SqPack magic"));
+        }
+        let header_size = read_u32_le(&data, 0x0C).ok_or_else(|| invalid("truncated header"))?;
+        let index_header = header_size as usize;
+        let (entries_offset, entries_size) = read_u32_le(&data, index_header + 0x08)
+            .zip(read_u32_le(&data, index_header + 0x0C))
+            .ok_or_else(|| invalid("truncated index header"))?;
+        let entries_end = entries_offset
+            .checked_add(entries_size)
+            .ok_or_else(|| invalid("index entries out of range"))?;
+        let entries = data
+            .get(entries_offset as usize..entries_end as usize)
+            .ok_or_else(|| invalid("truncated index entries"))?
+            .chunks_exact(16)
+            .map(|entry| {
+                let key = u64::from_le_bytes(entry[0..8].try_into().unwrap());
+                let data = u32::from_le_bytes(entry[8..12].try_into().unwrap());
+                let location = Location {
+                    data_file: (data >> 1) & 0b111,
+                    offset: u64::from(data & !0xF) * 0x08,
+                };
+                (key, location)
+            })
+            .collect();
+        Ok(Category {
+            base: index.with_extension(""),
+            entries,
+        })
+    }
+
+    pub fn contains(&self, path: &str) -> bool {
+        self.entries.contains_key(&index_key(path))
+    }
+
+    /// Reads a file from the category by its path, ex. `exd/emote.exh`.
+    pub fn read_file(&self, path: &str) -> Result<Vec<u8>> {
+        let location = self
+            .entries
+            .get(&index_key(path))
+            .ok_or_else(|| SqPackError::FileNotFound(path.to_string()))?;
+        let mut dat = self.base.clone().into_os_string();
+        dat.push(format!(".dat{}", location.data_file));
+        debug!("reading {} from {:?}", path, dat);
+        let mut file = File::open(&dat)?;
+        let invalid = |reason: &str| SqPackError::InvalidFile {
+            file: path.to_string(),
+            reason: reason.to_string(),
+        };
+
+        let file_size = file.metadata()?.len();
+        file.seek(SeekFrom::Start(location.offset))?;
+        let mut header = [0; 24];
+        file.read_exact(&mut header)?;
+        let header_size = read_u32_le(&header, 0).unwrap_or_default();
+        let file_type = read_u32_le(&header, 4).unwrap_or_default();
+        let raw_size = read_u32_le(&header, 8).unwrap_or_default();
+        let block_count = read_u32_le(&header, 20).unwrap_or_default();
+        if file_type != STANDARD_FILE {
+            return Err(invalid("not a standard file"));
+        }
+        // both sizes come from the file, so check them before allocating
+        if u64::from(block_count) * 8 > file_size.saturating_sub(location.offset) {
+            return Err(invalid("block table exceeds its data file"));
+        }
+        if u64::from(raw_size) > u64::from(block_count) * MAX_BLOCK_SIZE {
+            return Err(invalid("size exceeds its blocks"));
+        }
+        let mut blocks = vec![0; block_count as usize * 8];
+        file.read_exact(&mut blocks)?;
+
+        let mut contents = Vec::with_capacity(raw_size as usize);
+        for block in blocks.chunks_exact(8) {
+            let block_offset = read_u32_le(block, 0).unwrap_or_default();
+            file.seek(SeekFrom::Start(
+                location.offset + u64::from(header_size) + u64::from(block_offset),
+            ))?;
+            let mut block_header = [0; 16];
+            file.read_exact(&mut block_header)?;
+            let compressed_size = read_u32_le(&block_header, 8).unwrap_or_default();
+            let uncompressed_size = read_u32_le(&block_header, 12).unwrap_or_default();
+            if u64::from(uncompressed_size) > MAX_BLOCK_SIZE {
+                return Err(invalid("block exceeds the largest block size"));
+            }
+            if compressed_size == UNCOMPRESSED_BLOCK {
+                (&mut file)
+                    .take(u64::from(uncompressed_size))
+                    .read_to_end(&mut contents)?;
+            } else {
+                // one byte past the declared size is enough to tell the block is too large
+                let inflated = DeflateDecoder::new((&mut file).take(u64::from(compressed_size)))
+                    .take(u64::from(uncompressed_size) + 1)
+                    .read_to_end(&mut contents)?;
+                if inflated as u64 > u64::from(uncompressed_size) {
+                    return Err(invalid("block inflates past its size"));
+                }
+            }
+        }
+        if contents.len() != raw_size as usize {
+            return Err(invalid("size does not match its header"));
+        }
+        Ok(contents)
+    }
+}
+
+/// The game's sqpack archives, opened from the `game` folder of an installation.
+#[derive(Debug)]
+pub struct SqPack {
+    exd: Category,
+}
+
+impl SqPack {
+    /// Opens the archives under `game_dir`, ex. `C:/.../FINAL FANTASY XIV - A Realm Reborn/game`.
+    pub fn open(game_dir: impl AsRef<Path>) -> Result<SqPack> {
+        Ok(SqPack {
+            exd: Category::open(game_dir.as_ref().join("sqpack/ffxiv/0a0000.win32.index"))?,
+        })
+    }
+
+    /// The category holding the excel sheets.
+    pub fn exd(&self) -> &Category {
+        &self.exd
+    }
+}
+
+fn read_u32_le(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        bytes.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
@@ -0,0 +1,355 @@
+//! Reading excel sheets, which are stored as a header (`.exh`) describing the columns, pages and
+//! languages, and one data file (`.exd`) per page and language. Both are big-endian.
+
+use std::collections::BTreeMap;
+
+use crate::sestring::SeString;
+
+use super::{Category, Result, SqPackError};
+
+/// The language of a sheet's data files, as listed in its header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExcelLanguage {
+    /// sheets which are the same in every language
+    None = 0,
+    Japanese = 1,
+    English = 2,
+    German = 3,
+    French = 4,
+    ChineseSimplified = 5,
+    ChineseTraditional = 6,
+    Korean = 7,
+}
+
+impl ExcelLanguage {
+    fn from_id(id: u8) -> Option<ExcelLanguage> {
+        Some(match id {
+            0 => ExcelLanguage::None,
+            1 => ExcelLanguage::Japanese,
+            2 => ExcelLanguage::English,
+            3 => ExcelLanguage::German,
+            4 => ExcelLanguage::French,
+            5 => ExcelLanguage::ChineseSimplified,
+            6 => ExcelLanguage::ChineseTraditional,
+            7 => ExcelLanguage::Korean,
+            _ => return None,
+        })
+    }
+
+    pub fn id(&self) -> u8 {
+        *self as u8
+    }
+
+    /// The suffix of the language's data files, ex. `emote_0_en.exd`.
+    pub fn suffix(&self) -> Option<&'static str> {
+        Some(match self {
+            ExcelLanguage::None => return None,
+            ExcelLanguage::Japanese => "ja",
+            ExcelLanguage::English => "en",
+            ExcelLanguage::German => "de",
+            ExcelLanguage::French => "fr",
+            ExcelLanguage::ChineseSimplified => "chs",
+            ExcelLanguage::ChineseTraditional => "cht",
+            ExcelLanguage::Korean => "ko",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    String,
+    Bool,
+    Int8,
+    UInt8,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Float32,
+    Int64,
+    UInt64,
+    /// a single bit of a byte, used to pack several bools together
+    PackedBool(u8),
+}
+
+impl ColumnType {
+    fn from_id(id: u16) -> Option<ColumnType> {
+        Some(match id {
+            0x0 => ColumnType::String,
+            0x1 => ColumnType::Bool,
+            0x2 => ColumnType::Int8,
+            0x3 => ColumnType::UInt8,
+            0x4 => ColumnType::Int16,
+            0x5 => ColumnType::UInt16,
+            0x6 => ColumnType::Int32,
+            0x7 => ColumnType::UInt32,
+            0x9 => ColumnType::Float32,
+            0xA => ColumnType::Int64,
+            0xB => ColumnType::UInt64,
+            0x19..=0x20 => ColumnType::PackedBool((id - 0x19) as u8),
+            _ => return None,
+        })
+    }
+
+    pub fn id(&self) -> u16 {
+        match self {
+            ColumnType::String => 0x0,
+            ColumnType::Bool => 0x1,
+            ColumnType::Int8 => 0x2,
+            ColumnType::UInt8 => 0x3,
+            ColumnType::Int16 => 0x4,
+            ColumnType::UInt16 => 0x5,
+            ColumnType::Int32 => 0x6,
+            ColumnType::UInt32 => 0x7,
+            ColumnType::Float32 => 0x9,
+            ColumnType::Int64 => 0xA,
+            ColumnType::UInt64 => 0xB,
+            ColumnType::PackedBool(bit) => 0x19 + u16::from(*bit),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Column {
+    pub kind: ColumnType,
+    /// offset of the column within the fixed size part of a row
+    pub offset: u16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Page {
+    pub start_id: u32,
+    pub row_count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExcelHeader {
+    /// size of the fixed size part of each row, after which its strings are stored
+    pub data_offset: u16,
+    pub columns: Vec<Column>,
+    pub pages: Vec<Page>,
+    pub languages: Vec<ExcelLanguage>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(SeString),
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f32),
+}
+
+impl Value {
+    /// The value as an integer, if it is one.
+    pub fn as_u32(&self) -> Option<u32> {
+        match self {
+            Value::Int(i) => u32::try_from(*i).ok(),
+            Value::UInt(u) => u32::try_from(*u).ok(),
+            Value::Bool(b) => Some(u32::from(*b)),
+            _ => None,
+        }
+    }
+
+    /// The value in the text form used by SaintCoinach's csv exports.
+    pub fn to_text(&self) -> String {
+        match self {
+            Value::String(s) => s.to_macro_text(),
+            Value::Bool(b) => b.to_string(),
+            Value::Int(i) => i.to_string(),
+            Value::UInt(u) => u.to_string(),
+            Value::Float(f) => f.to_string(),
+        }
+    }
+}
+
+struct BigEndian<'a> {
+    file: &'a str,
+    bytes: &'a [u8],
+}
+
+impl<'a> BigEndian<'a> {
+    fn get<const N: usize>(&self, offset: usize) -> Result<[u8; N]> {
+        self.bytes
+            .get(offset..offset + N)
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| self.invalid("truncated"))
+    }
+
+    fn u16(&self, offset: usize) -> Result<u16> {
+        self.get(offset).map(u16::from_be_bytes)
+    }
+
+    fn u32(&self, offset: usize) -> Result<u32> {
+        self.get(offset).map(u32::from_be_bytes)
+    }
+
+    fn invalid(&self, reason: &str) -> SqPackError {
+        SqPackError::InvalidFile {
+            file: self.file.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl ExcelHeader {
+    /// Reads the header of a sheet by its name, ex. `Emote`.
+    pub fn read(category: &Category, name: &str) -> Result<ExcelHeader> {
+        let exh = format!("exd/{}.exh", name.to_lowercase());
+        Self::parse(&exh, &category.read_file(&exh)?)
+    }
+
+    pub fn parse(file: &str, bytes: &[u8]) -> Result<ExcelHeader> {
+        let data = BigEndian { file, bytes };
+        if !bytes.starts_with(b"EXHF") {
+            return Err(data.invalid("missing EXHF magic"));
+        }
+        let data_offset = data.u16(0x06)?;
+        let column_count = data.u16(0x08)? as usize;
+        let page_count = data.u16(0x0A)? as usize;
+        let language_count = data.u16(0x0C)? as usize;
+
+        let columns_start = 0x20;
+        let columns = (0..column_count)
+            .map(|i| {
+                let offset = columns_start + i * 4;
+                let kind = ColumnType::from_id(data.u16(offset)?)
+                    .ok_or_else(|| data.invalid("unknown column type"))?;
+                Ok(Column {
+                    kind,
+                    offset: data.u16(offset + 2)?,
+                })
+            })
+            .collect::<Result<_>>()?;
+        let pages_start = columns_start + column_count * 4;
+        let pages = (0..page_count)
+            .map(|i| {
+                let offset = pages_start + i * 8;
+                Ok(Page {
+                    start_id: data.u32(offset)?,
+                    row_count: data.u32(offset + 4)?,
+                })
+            })
+            .collect::<Result<_>>()?;
+        // languages are stored as a byte followed by padding
+        let languages_start = pages_start + page_count * 8;
+        let languages = (0..language_count)
+            .map(|i| {
+                let [id] = data.get(languages_start + i * 2)?;
+                ExcelLanguage::from_id(id).ok_or_else(|| data.invalid("unknown language"))
+            })
+            .collect::<Result<_>>()?;
+        Ok(ExcelHeader {
+            data_offset,
+            columns,
+            pages,
+            languages,
+        })
+    }
+}
+
+/// The rows of a sheet in a single language, keyed by row id. Sheets with subrows only have
+/// their first subrow read.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExcelSheet {
+    pub name: String,
+    pub header: ExcelHeader,
+    pub rows: BTreeMap<u32, Vec<Value>>,
+}
+
+impl ExcelSheet {
+    /// Reads every page of a sheet by its name, ex. `Emote`. Sheets which are the same in
+    /// every language are read regardless of the language given.
+    pub fn read(category: &Category, name: &str, language: ExcelLanguage) -> Result<ExcelSheet> {
+        let header = ExcelHeader::read(category, name)?;
+        let language = if header.languages.contains(&language) {
+            language
+        } else if header.languages.contains(&ExcelLanguage::None) {
+            ExcelLanguage::None
+        } else {
+            return Err(SqPackError::FileNotFound(format!(
+                "{} ({:?})",
+                name, language
+            )));
+        };
+        let mut rows = BTreeMap::new();
+        for page in &header.pages {
+            let exd = match language.suffix() {
+                Some(suffix) => format!("exd/{}_{}_{}.exd", name, page.start_id, suffix),
+                None => format!("exd/{}_{}.exd", name, page.start_id),
+            }
+            .to_lowercase();
+            let bytes = category.read_file(&exd)?;
+            Self::read_page(name, &header, &exd, &bytes, &mut rows)?;
+        }
+        Ok(ExcelSheet {
+            name: name.to_string(),
+            header,
+            rows,
+        })
+    }
+
+    fn read_page(
+        name: &str,
+        header: &ExcelHeader,
+        file: &str,
+        bytes: &[u8],
+        rows: &mut BTreeMap<u32, Vec<Value>>,
+    ) -> Result<()> {
+        let data = BigEndian { file, bytes };
+        if !bytes.starts_with(b"EXDF") {
+            return Err(data.invalid("missing EXDF magic"));
+        }
+        let index_size = data.u32(0x08)? as usize;
+        for entry in (0x20..0x20 + index_size).step_by(8) {
+            let row_id = data.u32(entry)?;
+            // each row starts with its size and number of subrows
+            let row_start = data.u32(entry + 4)? as usize + 6;
+            let strings_start = row_start + header.data_offset as usize;
+            let values = header
+                .columns
+                .iter()
+                .map(|column| {
+                    let offset = row_start + column.offset as usize;
+                    Ok(match column.kind {
+                        ColumnType::String => {
+                            let start = strings_start + data.u32(offset)? as usize;
+                            let len = bytes
+                                .get(start..)
+                                .and_then(|rest| rest.iter().position(|&b| b == 0))
+                                .ok_or_else(|| data.invalid("unterminated string"))?;
+                            let string =
+                                SeString::decode(&bytes[start..start + len]).map_err(|source| {
+                                    SqPackError::SeString {
+                                        sheet: name.to_string(),
+                                        row: row_id,
+                                        source,
+                                    }
+                                })?;
+                            Value::String(string)
+                        }
+                        ColumnType::Bool => Value::Bool(data.get::<1>(offset)?[0] != 0),
+                        ColumnType::Int8 => Value::Int(i8::from_be_bytes(data.get(offset)?).into()),
+                        ColumnType::UInt8 => Value::UInt(data.get::<1>(offset)?[0].into()),
+                        ColumnType::Int16 => {
+                            Value::Int(i16::from_be_bytes(data.get(offset)?).into())
+                        }
+                        ColumnType::UInt16 => Value::UInt(data.u16(offset)?.into()),
+                        ColumnType::Int32 => {
+                            Value::Int(i32::from_be_bytes(data.get(offset)?).into())
+                        }
+                        ColumnType::UInt32 => Value::UInt(data.u32(offset)?.into()),
+                        ColumnType::Float32 => Value::Float(f32::from_be_bytes(data.get(offset)?)),
+                        ColumnType::Int64 => Value::Int(i64::from_be_bytes(data.get(offset)?)),
+                        ColumnType::UInt64 => Value::UInt(u64::from_be_bytes(data.get(offset)?)),
+                        ColumnType::PackedBool(bit) => {
+                            Value::Bool(data.get::<1>(offset)?[0] & (1 << bit) != 0)
+                        }
+                    })
+                })
+                .collect::<Result<_>>()?;
+            rows.insert(row_id, values);
+        }
+        Ok(())
+    }
+}
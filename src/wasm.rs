@@ -0,0 +1,111 @@
+//! Bindings for loading a repository and rendering emotes from javascript, through
+//! wasm-bindgen.
+//!
+//! ```js
+//! const repo = EmoteRepository.fromJson(json);
+//! repo.render("/surprised", "en", { name: "Alice", gender: "female", is_self: true }, { name: "Bob", gender: "male" });
+//! ```
+//!
+//! Characters are deserialized as [Character], which also accepts `isPc` and `isSelf`.
+
+use thiserror::Error;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    log_message::{
+        condition::{Character, LogMessageAnswers, LogMessageAnswersError},
+        process_log_message, EmoteTextError,
+    },
+    repository::{EmoteView, Language, LogMessageRepository, LogMessageRepositoryError},
+};
+
+#[derive(Debug, Error)]
+pub enum WasmError {
+    #[error("Unknown language ({0})")]
+    UnknownLanguage(String),
+    #[error(transparent)]
+    Repository(#[from] LogMessageRepositoryError),
+    #[error(transparent)]
+    Answers(#[from] LogMessageAnswersError),
+    #[error(transparent)]
+    EmoteText(#[from] EmoteTextError),
+}
+
+#[wasm_bindgen]
+pub struct EmoteRepository(LogMessageRepository);
+
+#[wasm_bindgen]
+impl EmoteRepository {
+    /// Loads a repository from json, see [LogMessageRepository::from_json].
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<EmoteRepository, JsError> {
+        Ok(EmoteRepository(LogMessageRepository::from_json(json)?))
+    }
+
+    /// Every command of every emote, sorted.
+    pub fn commands(&self) -> Vec<String> {
+        let mut commands: Vec<_> = self.0.emote_list().cloned().collect();
+        commands.sort_unstable();
+        commands
+    }
+
+    #[wasm_bindgen(js_name = hasEmote)]
+    pub fn has_emote(&self, command: &str) -> bool {
+        self.0.contains_emote(command)
+    }
+
+    /// The emote with the command as serialized by [EmoteView], or undefined.
+    pub fn lookup(&self, command: &str) -> Result<JsValue, JsError> {
+        match self.0.messages(command) {
+            Ok(data) => Ok(serde_wasm_bindgen::to_value(&EmoteView::from(
+                data.as_ref(),
+            ))?),
+            Err(LogMessageRepositoryError::NotFound) => Ok(JsValue::UNDEFINED),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Renders the emote's log message, where characters are objects as described by
+    /// [Character]. The untargeted message is rendered when the target is undefined or null.
+    pub fn render(
+        &self,
+        command: &str,
+        language: &str,
+        origin: JsValue,
+        target: JsValue,
+    ) -> Result<String, JsError> {
+        let origin = serde_wasm_bindgen::from_value(origin)?;
+        let target = serde_wasm_bindgen::from_value(target)?;
+        Ok(self.render_characters(command, language, origin, target)?)
+    }
+}
+
+impl EmoteRepository {
+    pub fn repository(&self) -> &LogMessageRepository {
+        &self.0
+    }
+
+    /// [EmoteRepository::render] with characters which have already been converted from
+    /// javascript.
+    pub fn render_characters(
+        &self,
+        command: &str,
+        language: &str,
+        origin: Character,
+        target: Option<Character>,
+    ) -> Result<String, WasmError> {
+        let language = Language::from_code(language)
+            .ok_or_else(|| WasmError::UnknownLanguage(language.to_string()))?;
+        let (log_msg, answers) = match target {
+            Some(target) => (
+                self.0.targeted(command, language)?,
+                LogMessageAnswers::new(origin, target)?,
+            ),
+            None => (
+                self.0.untargeted(command, language)?,
+                LogMessageAnswers::untargeted(origin),
+            ),
+        };
+        Ok(process_log_message(log_msg, &answers)?)
+    }
+}
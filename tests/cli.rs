@@ -0,0 +1,322 @@
+#![cfg(feature = "cli")]
+
+use std::{
+    io::Write,
+    path::PathBuf,
+    process::{Command, Output, Stdio},
+};
+
+const REPO: &str = r#"[
+    {
+        "id": 1,
+        "name": "Surprised",
+        "commands": ["/surprised"],
+        "en": {
+            "targeted": "<If(Equal(ObjectParameter(1),ObjectParameter(2)))>You look<Else/><If(PlayerParameter(7))><SheetEn(ObjStr,2,PlayerParameter(7),1,1)/><Else/>ObjectParameter(2)</If> looks</If> at <If(Equal(ObjectParameter(1),ObjectParameter(3)))>you<Else/><If(PlayerParameter(8))><SheetEn(ObjStr,2,PlayerParameter(8),1,1)/><Else/>ObjectParameter(3)</If></If> in surprise.",
+            "untargeted": "<If(Equal(ObjectParameter(1),ObjectParameter(2)))>You look<Else/><If(PlayerParameter(7))><SheetEn(ObjStr,2,PlayerParameter(7),1,1)/><Else/>ObjectParameter(2)</If> looks</If> surprised."
+        },
+        "ja": { "targeted": "ja targeted", "untargeted": "ja untargeted" }
+    }
+]"#;
+
+/// Writes the repository json to a temporary file, removed when dropped.
+struct RepoFile(PathBuf);
+
+impl RepoFile {
+    fn new(test: &str, repo: &str) -> std::io::Result<RepoFile> {
+        let path = std::env::temp_dir().join(format!(
+            "xiv-emote-cli-{}-{}.json",
+            test,
+            std::process::id()
+        ));
+        std::fs::write(&path, repo)?;
+        Ok(RepoFile(path))
+    }
+
+    fn run(&self, subcommand: &str, args: &[&str]) -> std::io::Result<Output> {
+        Command::new(env!("CARGO_BIN_EXE_xiv-emote"))
+            .arg(subcommand)
+            .arg("--json")
+            .arg(&self.0)
+            .args(args)
+            .output()
+    }
+}
+
+impl Drop for RepoFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn renders_emote() -> std::io::Result<()> {
+    let repo = RepoFile::new("render", REPO)?;
+    let output = repo.run("render", &["/surprised", "--origin", "Alice"])?;
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "Alice looks surprised.\n");
+    let output = repo.run(
+        "render",
+        &[
+            "/surprised",
+            "--origin",
+            "Alice",
+            "--target",
+            "Bob",
+            "--target-self",
+        ],
+    )?;
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "Alice looks at you in surprise.\n");
+    let output = repo.run(
+        "render",
+        &["/surprised", "--origin-self", "--target", "Bob"],
+    )?;
+    assert_eq!(stdout(&output), "You look at Bob in surprise.\n");
+    // target flags are ignored without a target
+    let output = repo.run("render", &["/surprised", "--origin-self", "--target-self"])?;
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "You look surprised.\n");
+    Ok(())
+}
+
+#[test]
+fn renders_all_variants() -> std::io::Result<()> {
+    let repo = RepoFile::new("variants", REPO)?;
+    let output = repo.run("render", &["/surprised", "--all-variants"])?;
+    assert!(output.status.success());
+    let out = stdout(&output);
+    let lines: Vec<_> = out.lines().collect();
+    // targeted: 4 origins by 4 targets, 2 self origins by 4 targets, 4 origins by 2 self targets
+    // untargeted: 4 origins, 2 self origins
+    assert_eq!(lines.len(), 16 + 8 + 8 + 4 + 2);
+    assert!(lines.contains(
+        &"[targeted, origin: male npc, target: female player (self)] Origin looks at you in surprise."
+    ));
+    assert!(lines.contains(&"[untargeted, origin: female player (self)] You look surprised."));
+    Ok(())
+}
+
+#[test]
+fn fails_on_unknown_emote() -> std::io::Result<()> {
+    let repo = RepoFile::new("unknown", REPO)?;
+    let output = repo.run("render", &["/unknown"])?;
+    assert!(!output.status.success());
+    assert!(stdout(&output).is_empty());
+    assert!(!output.stderr.is_empty());
+    Ok(())
+}
+
+const BROKEN: &str = r#"[
+    {
+        "id": 1,
+        "name": "Unknown",
+        "commands": ["/unknown"],
+        "en": { "targeted": "<Split(ObjectParameter(2), )/>", "untargeted": "ok" },
+        "ja": { "targeted": "<If(PlayerParameter(99))>a<Else/>b</If>", "untargeted": "ok" }
+    },
+    {
+        "id": 2,
+        "name": "Other",
+        "commands": ["/other"],
+        "en": { "targeted": "<Split(a)/>", "untargeted": "ok" },
+        "ja": { "targeted": "ok", "untargeted": "ok" }
+    }
+]"#;
+
+#[test]
+fn checks_repository() -> std::io::Result<()> {
+    let repo = RepoFile::new("check", REPO)?;
+    let output = repo.run("check", &[])?;
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "checked 4 messages, 0 failed\n");
+    let repo = RepoFile::new("check-broken", BROKEN)?;
+    let output = repo.run("check", &[])?;
+    assert!(!output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "en:\n  unknown tag Split: 2 (Unknown, Other)\n\
+         ja:\n  unknown condition PlayerParameter(99): 1 (Unknown)\n\
+         checked 8 messages, 3 failed\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn repl_toggles_conditions() -> std::io::Result<()> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_xiv-emote"))
+        .args(["repl", "--origin", "Alice"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let input = "<If(Equal(ObjectParameter(1),ObjectParameter(2)))>You look<Else/><If(PlayerParameter(7))><SheetEn(ObjStr,2,PlayerParameter(7),1,1)/><Else/>ObjectParameter(2)</If> looks</If> surprised.\n\
+                 1\n\
+                 <If(\n";
+    child
+        .stdin
+        .take()
+        .expect("stdin was piped")
+        .write_all(input.as_bytes())?;
+    let output = child.wait_with_output()?;
+    assert!(output.status.success());
+    let out = stdout(&output);
+    assert!(out.contains("  [!IsSelfOrigin, IsOriginPlayer] {PlayerOriginNameEn}\n"));
+    assert!(out.contains("  1. IsSelfOrigin = false\n  2. IsOriginPlayer = false\n"));
+    let outputs: Vec<_> = out
+        .lines()
+        .filter_map(|line| line.strip_prefix("output: "))
+        .collect();
+    assert_eq!(
+        outputs,
+        vec!["Alice looks surprised.", "You look surprised."]
+    );
+    // parse errors are reported without ending the session
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("error: "));
+    Ok(())
+}
+
+/// A running `serve` subcommand, killed when dropped.
+#[cfg(feature = "server")]
+struct Server {
+    child: std::process::Child,
+    url: String,
+}
+
+#[cfg(feature = "server")]
+impl Server {
+    fn start(repo: &RepoFile) -> std::io::Result<Server> {
+        use std::io::{BufRead, BufReader};
+
+        let mut child = Command::new(env!("CARGO_BIN_EXE_xiv-emote"))
+            .arg("serve")
+            .arg("--json")
+            .arg(&repo.0)
+            .args(["--address", "127.0.0.1:0"])
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let mut line = String::new();
+        BufReader::new(child.stdout.take().expect("stdout was piped")).read_line(&mut line)?;
+        let url = line
+            .trim()
+            .strip_prefix("listening on ")
+            .expect("server prints its address")
+            .to_string();
+        Ok(Server { child, url })
+    }
+
+    /// The status code and json body of a response, including error responses.
+    fn request(&self, method: &str, path: &str, body: Option<&str>) -> (u16, serde_json::Value) {
+        use std::{io::Read, net::TcpStream};
+
+        let address = self
+            .url
+            .strip_prefix("http://")
+            .expect("server url is http");
+        let mut stream = TcpStream::connect(address).expect("couldn't connect to server");
+        let body = body.unwrap_or_default();
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            method,
+            path,
+            address,
+            body.len(),
+            body
+        )
+        .expect("couldn't send request");
+        let mut response = String::new();
+        stream
+            .read_to_string(&mut response)
+            .expect("couldn't read response");
+        let (head, body) = response
+            .split_once("\r\n\r\n")
+            .expect("response has a body");
+        let status = head
+            .split(' ')
+            .nth(1)
+            .and_then(|status| status.parse().ok())
+            .expect("response has a status");
+        (
+            status,
+            serde_json::from_str(body).expect("response is json"),
+        )
+    }
+}
+
+#[cfg(feature = "server")]
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(feature = "server")]
+#[test]
+fn serves_emotes() -> std::io::Result<()> {
+    use serde_json::json;
+
+    let repo = RepoFile::new("serve", REPO)?;
+    let server = Server::start(&repo)?;
+    let (status, emotes) = server.request("GET", "/emotes", None);
+    assert_eq!(status, 200);
+    assert_eq!(
+        emotes,
+        json!([{ "id": 1, "name": "Surprised", "commands": ["/surprised"] }])
+    );
+    let (status, emote) = server.request("GET", "/emotes/%2FSurprised", None);
+    assert_eq!(status, 200);
+    assert_eq!(emote["names"], json!({ "en": "Surprised" }));
+    assert_eq!(emote["messages"]["ja"]["targeted"], "ja targeted");
+    let (status, _) = server.request("GET", "/emotes/unknown", None);
+    assert_eq!(status, 404);
+    let (status, _) = server.request("DELETE", "/emotes", None);
+    assert_eq!(status, 405);
+    Ok(())
+}
+
+#[cfg(feature = "server")]
+#[test]
+fn serves_rendered_messages() -> std::io::Result<()> {
+    let repo = RepoFile::new("serve-render", REPO)?;
+    let server = Server::start(&repo)?;
+    let (status, body) = server.request(
+        "POST",
+        "/render",
+        Some(r#"{ "command": "/surprised", "origin": { "name": "Alice", "gender": "female" } }"#),
+    );
+    assert_eq!(status, 200);
+    assert_eq!(body["message"], "Alice looks surprised.");
+    let (status, body) = server.request(
+        "POST",
+        "/render",
+        Some(
+            r#"{
+                "command": "surprised",
+                "language": "en",
+                "origin": { "name": "Alice", "gender": "female", "is_self": true },
+                "target": { "name": "Bob", "gender": "male", "is_pc": false }
+            }"#,
+        ),
+    );
+    assert_eq!(status, 200);
+    assert_eq!(body["message"], "You look at Bob in surprise.");
+    let (status, body) = server.request(
+        "POST",
+        "/render",
+        Some(r#"{ "command": "/surprised", "language": "xx", "origin": { "name": "Alice", "gender": "female" } }"#),
+    );
+    assert_eq!(status, 400);
+    assert_eq!(body["error"], "Unknown language (xx)");
+    let (status, _) = server.request("POST", "/render", Some("{}"));
+    assert_eq!(status, 400);
+    let (status, body) = server.request("POST", "/render", Some(&" ".repeat(65 * 1024)));
+    assert_eq!(status, 413);
+    assert_eq!(body["error"], "Request body too large");
+    Ok(())
+}
@@ -0,0 +1,59 @@
+#![allow(dead_code)]
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+};
+
+#[cfg(any(feature = "xivapi", feature = "xivapi-async"))]
+use {std::time::Duration, xiv_emote_parser::repository::XivapiConfig};
+
+pub const EMPTY_PAGE: &str = r#"{"pagination":{"page_next":null},"results":[{"id":1}]}"#;
+
+pub const SURPRISED_PAGE: &str = r#"{"pagination":{"page_next":null},"results":[{"id":1,"name":"Surprised","log_message_targeted":{"text_en":"a","text_ja":"b"},"log_message_untargeted":{"text_en":"c","text_ja":"d"},"text_command":{"command_en":"/surprised"}}]}"#;
+
+/// Serves each response in order (repeating the last one), returning the
+/// url to request and a counter of requests received.
+pub fn serve(responses: Vec<(u16, &'static str)>) -> (String, Arc<AtomicU32>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("couldn't bind test server");
+    let url = format!("http://{}/emote", listener.local_addr().unwrap());
+    let count = Arc::new(AtomicU32::new(0));
+    let thread_count = count.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            let i = thread_count.fetch_add(1, Ordering::SeqCst) as usize;
+            let (status, body) = responses[i.min(responses.len() - 1)];
+            write!(
+                stream,
+                "HTTP/1.1 {} Test\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+        }
+    });
+    (url, count)
+}
+
+#[cfg(any(feature = "xivapi", feature = "xivapi-async"))]
+pub fn test_config(url: String, retry_budget: u32) -> XivapiConfig {
+    XivapiConfig {
+        url,
+        timeout: Duration::from_secs(5),
+        retry_budget,
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(5),
+        include_motion_only: false,
+    }
+}
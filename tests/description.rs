@@ -0,0 +1,110 @@
+#![allow(clippy::result_large_err)]
+
+use xiv_emote_parser::log_message::{
+    description::{
+        description_to_plain_text, description_to_segments, parse_description, ColorTag,
+        ColorTagName, DescriptionPart, StyledSegment,
+    },
+    EmoteTextError,
+};
+
+const SURPRISED_EN: &str = "USAGE:\n\n\u{3000}/surprised [subcommand]\n\n\n\n→Act surprised.\n\n\n\n>>Subcommands:\n\n\u{3000}<UIForeground>F201FA</UIForeground><UIGlow>F201FB</UIGlow>motion<UIGlow>01</UIGlow><UIForeground>01</UIForeground>    Perform motion only.\n\n\n\nBoth text and motion will be displayed when no subcommand is specified.";
+
+#[test]
+fn can_parse_color_tags() -> Result<(), EmoteTextError> {
+    let description =
+        parse_description("a<UIForeground>F201FA</UIForeground>b<UIForeground>01</UIForeground>")?;
+    assert_eq!(
+        description.0,
+        vec![
+            DescriptionPart::Text("a".to_string()),
+            DescriptionPart::Color(ColorTag::Set {
+                name: ColorTagName::UIForeground,
+                color: 506
+            }),
+            DescriptionPart::Text("b".to_string()),
+            DescriptionPart::Color(ColorTag::Set {
+                name: ColorTagName::UIForeground,
+                color: 0
+            }),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn can_parse_stack_color_reset() -> Result<(), EmoteTextError> {
+    let description = "<UIForeground>F201F4</UIForeground>red<UIForeground>EC</UIForeground>plain";
+    assert_eq!(
+        parse_description(description)?.0[2],
+        DescriptionPart::Color(ColorTag::Reset {
+            name: ColorTagName::UIForeground
+        })
+    );
+    let segments = description_to_segments(description)?;
+    assert_eq!(segments[0].foreground, Some(500));
+    assert_eq!(segments[1].text, "plain");
+    assert_eq!(segments[1].foreground, None);
+    Ok(())
+}
+
+#[test]
+fn rejects_invalid_color_tags() {
+    assert!(parse_description("<UIForeground>F201FA</UIGlow>").is_err());
+    assert!(parse_description("<UIForeground>F2</UIForeground>").is_err());
+}
+
+#[test]
+fn can_render_plain_text() -> Result<(), EmoteTextError> {
+    let text = description_to_plain_text(SURPRISED_EN)?;
+    assert!(text.contains("\u{3000}motion    Perform motion only."));
+    assert!(!text.contains('<'));
+    Ok(())
+}
+
+#[test]
+fn can_render_segments() -> Result<(), EmoteTextError> {
+    let segments = description_to_segments(SURPRISED_EN)?;
+    assert_eq!(segments.len(), 3);
+    assert_eq!(
+        segments[1],
+        StyledSegment {
+            text: "motion".to_string(),
+            foreground: Some(506),
+            glow: Some(507),
+        }
+    );
+    assert_eq!(segments[2].foreground, None);
+    assert_eq!(segments[2].glow, None);
+    assert!(segments[2].text.starts_with("    Perform motion only."));
+    Ok(())
+}
+
+#[test]
+fn can_parse_all_descriptions() -> Result<(), EmoteTextError> {
+    let data = [
+        include_str!("../emote-221102-1.json"),
+        include_str!("../emote-221102-2.json"),
+        include_str!("../emote-221102-3.json"),
+    ];
+    for d in data {
+        let v: serde_json::Value = serde_json::from_str(d).expect("couldn't parse test json");
+        let emotes = v["Results"]
+            .as_array()
+            .expect("test json didn't contain Results array");
+        for emote in emotes {
+            for key in [
+                "Description_en",
+                "Description_ja",
+                "Description_de",
+                "Description_fr",
+            ] {
+                if let Some(description) = emote["TextCommand"][key].as_str() {
+                    let text = description_to_plain_text(description)?;
+                    assert!(!text.contains("<UI"), "{} still has markup", text);
+                }
+            }
+        }
+    }
+    Ok(())
+}
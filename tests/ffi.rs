@@ -0,0 +1,198 @@
+#![cfg(feature = "ffi")]
+
+use std::{
+    ffi::{c_char, CStr, CString},
+    io::ErrorKind,
+    path::Path,
+    process::Command,
+    ptr,
+};
+
+use xiv_emote_parser::ffi::*;
+
+const REPO: &str = r#"[
+    {
+        "id": 1,
+        "name": "Surprised",
+        "commands": ["/surprised"],
+        "en": {
+            "targeted": "<If(Equal(ObjectParameter(1),ObjectParameter(2)))>You look<Else/><If(PlayerParameter(7))><SheetEn(ObjStr,2,PlayerParameter(7),1,1)/><Else/>ObjectParameter(2)</If> looks</If> at <If(Equal(ObjectParameter(1),ObjectParameter(3)))>you<Else/><If(PlayerParameter(8))><SheetEn(ObjStr,2,PlayerParameter(8),1,1)/><Else/>ObjectParameter(3)</If></If> in surprise.",
+            "untargeted": "<If(Equal(ObjectParameter(1),ObjectParameter(2)))>You look<Else/><If(PlayerParameter(7))><SheetEn(ObjStr,2,PlayerParameter(7),1,1)/><Else/>ObjectParameter(2)</If> looks</If> surprised."
+        },
+        "ja": { "targeted": "<If(PlayerParameter(99))>a<Else/>b</If>", "untargeted": "ja untargeted" }
+    }
+]"#;
+
+/// A repository loaded through the C ABI, freed when dropped.
+struct Repo(*mut XivEmoteRepository);
+
+impl Repo {
+    fn load(json: &str) -> Result<Repo, XivEmoteStatus> {
+        let json = CString::new(json).expect("json has no null characters");
+        let mut repo = ptr::null_mut();
+        match unsafe { xiv_emote_repository_from_json(json.as_ptr(), &mut repo) } {
+            XivEmoteStatus::Ok => Ok(Repo(repo)),
+            status => Err(status),
+        }
+    }
+
+    fn render(
+        &self,
+        command: &str,
+        language: &str,
+        origin: &XivEmoteCharacter,
+        target: Option<&XivEmoteCharacter>,
+    ) -> Result<String, XivEmoteStatus> {
+        let command = CString::new(command).expect("command has no null characters");
+        let language = CString::new(language).expect("language has no null characters");
+        let mut out: *mut c_char = ptr::null_mut();
+        let status = unsafe {
+            xiv_emote_render(
+                self.0,
+                command.as_ptr(),
+                language.as_ptr(),
+                origin,
+                target.map_or(ptr::null(), |target| target as *const _),
+                &mut out,
+            )
+        };
+        if status != XivEmoteStatus::Ok {
+            assert!(out.is_null());
+            return Err(status);
+        }
+        let message = unsafe { CStr::from_ptr(out) }
+            .to_str()
+            .expect("message is utf-8")
+            .to_string();
+        unsafe { xiv_emote_string_free(out) };
+        Ok(message)
+    }
+}
+
+impl Drop for Repo {
+    fn drop(&mut self) {
+        unsafe { xiv_emote_repository_free(self.0) };
+    }
+}
+
+fn last_error() -> String {
+    let error = xiv_emote_last_error();
+    assert!(!error.is_null());
+    unsafe { CStr::from_ptr(error) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+const ALICE: &CStr = c"Alice";
+const BOB: &CStr = c"Bob";
+
+fn character(name: &CStr, gender: u8, is_pc: bool, is_self: bool) -> XivEmoteCharacter {
+    XivEmoteCharacter {
+        name: name.as_ptr(),
+        gender,
+        is_pc,
+        is_self,
+    }
+}
+
+#[test]
+fn can_render_through_c_abi() -> Result<(), XivEmoteStatus> {
+    let repo = Repo::load(REPO)?;
+    let alice = character(ALICE, XIV_EMOTE_GENDER_FEMALE, true, false);
+    let bob = character(BOB, XIV_EMOTE_GENDER_MALE, false, true);
+    assert_eq!(
+        repo.render("/surprised", "en", &alice, None)?,
+        "Alice looks surprised."
+    );
+    assert_eq!(
+        repo.render("surprised", "EN", &alice, Some(&bob))?,
+        "Alice looks at you in surprise."
+    );
+    assert_eq!(
+        repo.render("/surprised", "ja", &alice, None)?,
+        "ja untargeted"
+    );
+    let command = CString::new("/Surprised").expect("no null characters");
+    assert!(unsafe { xiv_emote_has_emote(repo.0, command.as_ptr()) });
+    assert!(!unsafe { xiv_emote_has_emote(repo.0, ptr::null()) });
+    Ok(())
+}
+
+#[test]
+fn errors_are_mapped_to_statuses() -> Result<(), XivEmoteStatus> {
+    assert!(matches!(Repo::load("[{"), Err(XivEmoteStatus::InvalidJson)));
+    assert_eq!(last_error(), "Invalid json input string");
+    let repo = Repo::load(REPO)?;
+    let alice = character(ALICE, XIV_EMOTE_GENDER_FEMALE, true, true);
+    let bob = character(BOB, XIV_EMOTE_GENDER_MALE, true, true);
+    assert_eq!(
+        repo.render("/unknown", "en", &alice, None),
+        Err(XivEmoteStatus::NotFound)
+    );
+    assert_eq!(
+        repo.render("/surprised", "xx", &alice, None),
+        Err(XivEmoteStatus::InvalidArgument)
+    );
+    assert_eq!(last_error(), "Unknown language (xx)");
+    assert_eq!(
+        repo.render("/surprised", "en", &character(ALICE, 2, true, false), None),
+        Err(XivEmoteStatus::InvalidArgument)
+    );
+    assert_eq!(
+        repo.render("/surprised", "en", &alice, Some(&bob)),
+        Err(XivEmoteStatus::MultipleSelves)
+    );
+    assert_eq!(
+        repo.render("/surprised", "ja", &alice, Some(&bob)),
+        Err(XivEmoteStatus::MultipleSelves)
+    );
+    let nobody = character(ALICE, XIV_EMOTE_GENDER_MALE, true, false);
+    assert_eq!(
+        repo.render("/surprised", "ja", &alice, Some(&nobody)),
+        Err(XivEmoteStatus::ProcessError)
+    );
+    let mut out = ptr::null_mut();
+    let status = unsafe {
+        xiv_emote_render(
+            repo.0,
+            ptr::null(),
+            c"en".as_ptr(),
+            &alice,
+            ptr::null(),
+            &mut out,
+        )
+    };
+    assert_eq!(status, XivEmoteStatus::NullArgument);
+    Ok(())
+}
+
+/// Checks that the header matches what the cbindgen cli generates from src/ffi.rs, when it is
+/// installed.
+#[test]
+fn header_is_up_to_date() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let output = match Command::new("cbindgen")
+        .current_dir(root)
+        .args(["--config", "cbindgen.toml", "src/ffi.rs"])
+        .output()
+    {
+        Ok(output) => output,
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            eprintln!("cbindgen is not installed, not checking the header");
+            return;
+        }
+        Err(e) => panic!("couldn't run cbindgen: {}", e),
+    };
+    assert!(
+        output.status.success(),
+        "couldn't generate header: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let path = root.join("include/xiv_emote_parser.h");
+    let existing = std::fs::read(&path).unwrap_or_default();
+    assert!(
+        existing == output.stdout,
+        "{} is out of date, regenerate it with cbindgen as described in the readme",
+        path.display()
+    );
+}
@@ -1,8 +1,11 @@
+#![allow(clippy::result_large_err)]
+
 use std::error::Error;
 
 use thiserror::Error;
 use xiv_emote_parser::log_message::{
-    condition::{Character, Gender, LogMessageAnswers},
+    condition::{Character, Condition, Gender, LogMessageAnswers},
+    parser::extract_condition_texts,
     process_log_message, EmoteTextError,
 };
 
@@ -54,6 +57,65 @@ fn can_parse_en_with_ast_gendered_speaker() -> Result<(), impl Error> {
     text.map(|_| ())
 }
 
+#[test]
+fn lists_conditions_in_order() -> Result<(), EmoteTextError> {
+    let log_msg = "<Clickable(<If(Equal(ObjectParameter(1),ObjectParameter(2)))>you<Else/><If(PlayerParameter(7))><SheetEn(ObjStr,2,PlayerParameter(7),1,1)/><Else/>ObjectParameter(2)</If></If>)/> <If(Equal(ObjectParameter(1),ObjectParameter(2)))>look<Else/>looks</If> at <If(Equal(ObjectParameter(1),ObjectParameter(3)))><If(PlayerParameter(8))><SheetEn(ObjStr,2,PlayerParameter(8),1,1)/><Else/>you</If><Else/><If(PlayerParameter(8))><SheetEn(ObjStr,2,PlayerParameter(8),1,1)/><Else/>ObjectParameter(3)</If></If> in surprise.";
+    assert_eq!(
+        extract_condition_texts(log_msg)?.conditions(),
+        vec![
+            Condition::IsSelfOrigin,
+            Condition::IsOriginPlayer,
+            Condition::IsSelfTarget,
+            Condition::IsTargetPlayer,
+        ]
+    );
+    Ok(())
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn serializes_condition_texts() -> Result<(), Box<dyn Error>> {
+    let log_msg = "<If(Equal(ObjectParameter(1),ObjectParameter(2)))>You look<Else/><If(PlayerParameter(7))><SheetEn(ObjStr,2,PlayerParameter(7),1,1)/><Else/>ObjectParameter(2)</If> looks</If> surprised.";
+    let texts = extract_condition_texts(log_msg)?;
+    let value = serde_json::to_value(&texts)?;
+    assert_eq!(
+        value[0],
+        serde_json::json!({
+            "conds": [{ "cond": "is_self_origin", "is_true": true }],
+            "text": { "static": "You look" },
+        })
+    );
+    assert_eq!(
+        value[1],
+        serde_json::json!({
+            "conds": [
+                { "cond": "is_self_origin", "is_true": false },
+                { "cond": "is_origin_player", "is_true": true },
+            ],
+            "text": { "dynamic": "player_origin_name_en" },
+        })
+    );
+    let round_trip: xiv_emote_parser::log_message::ast::types::ConditionTexts =
+        serde_json::from_value(value.clone())?;
+    assert_eq!(serde_json::to_value(&round_trip)?, value);
+    Ok(())
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn deserializes_character_with_defaults() -> Result<(), serde_json::Error> {
+    let character: Character = serde_json::from_str(r#"{ "name": "Alice", "gender": "female" }"#)?;
+    assert_eq!(
+        character,
+        Character::new("Alice", Gender::Female, true, false)
+    );
+    assert_eq!(
+        serde_json::to_value(&character)?,
+        serde_json::json!({ "name": "Alice", "gender": "female", "is_pc": true, "is_self": false })
+    );
+    Ok(())
+}
+
 #[test]
 fn can_parse_en_cry() -> Result<(), impl Error> {
     let log_msg = "<Clickable(<If(Equal(ObjectParameter(1),ObjectParameter(2)))>your<Else/><If(Equal(ObjectParameter(1),ObjectParameter(2)))>you<Else/><If(PlayerParameter(7))><SheetEn(ObjStr,2,PlayerParameter(7),1,1)/><Else/>ObjectParameter(2)</If></If>'s</If>)/> eyes brim over with tears.";
@@ -65,6 +127,50 @@ fn can_parse_en_cry() -> Result<(), impl Error> {
     text.map(|_| ())
 }
 
+#[test]
+fn can_parse_chs() -> Result<(), EmoteTextError> {
+    let log_msg = "<If(Equal(ObjectParameter(1),ObjectParameter(2)))>你<Else/><If(PlayerParameter(7))><Sheet(ObjStr,PlayerParameter(7),0)/><Else/>ObjectParameter(2)</If></If>对<If(Equal(ObjectParameter(1),ObjectParameter(3)))>你<Else/><If(PlayerParameter(8))><Sheet(ObjStr,PlayerParameter(8),0)/><Else/>ObjectParameter(3)</If></If>表示惊讶。";
+
+    let origin = Character::new("K'haldru Alaba", Gender::Female, true, true);
+    let target = Character::new("Puruo Jelly", Gender::Male, true, false);
+    let text = process_log_message(log_msg, &LogMessageAnswers::new(origin, target).unwrap())?;
+    assert_eq!(text, "你对Puruo Jelly表示惊讶。");
+    Ok(())
+}
+
+#[test]
+fn untargeted_answers_have_no_self_target() -> Result<(), EmoteTextError> {
+    let log_msg = "<If(Equal(ObjectParameter(1),ObjectParameter(2)))>You look<Else/>ObjectParameter(2) looks</If> surprised.";
+
+    let origin = Character::new("K'haldru Alaba", Gender::Female, true, true);
+    let answers = LogMessageAnswers::untargeted(origin.clone());
+    assert_eq!(answers.origin_character(), &origin);
+    assert!(!answers.target_character().is_self);
+    assert_eq!(
+        process_log_message(log_msg, &answers)?,
+        "You look surprised."
+    );
+    Ok(())
+}
+
+#[test]
+fn can_parse_ko() -> Result<(), EmoteTextError> {
+    let log_msg = "<If(Equal(ObjectParameter(1),ObjectParameter(2)))>당신<Else/><If(PlayerParameter(7))><Sheet(ObjStr,PlayerParameter(7),0)/><Else/>ObjectParameter(2)</If></If>은(는) <If(Equal(ObjectParameter(1),ObjectParameter(3)))>당신<Else/><If(PlayerParameter(8))><Sheet(ObjStr,PlayerParameter(8),0)/><Else/>ObjectParameter(3)</If></If>을(를) 보고 깜짝 놀랐습니다!";
+
+    let origin = Character::new("K'haldru Alaba", Gender::Female, true, true);
+    let target = Character::new("Puruo Jelly", Gender::Male, true, false);
+    let text = process_log_message(log_msg, &LogMessageAnswers::new(target, origin).unwrap())?;
+    assert_eq!(text, "Puruo Jelly은(는) 당신을(를) 보고 깜짝 놀랐습니다!");
+    Ok(())
+}
+
+#[test]
+fn rejects_markup_characters_in_text() {
+    assert!(extract_condition_texts("你好～").is_ok());
+    assert!(extract_condition_texts("<If(PlayerParameter(7))>a>b<Else/>c</If>").is_err());
+    assert!(extract_condition_texts("a = b").is_err());
+}
+
 #[derive(Debug, Error)]
 #[error("Failed to parse {name} ({error:?}) (original: {original})")]
 struct MessageTestError {
@@ -74,6 +180,7 @@ struct MessageTestError {
 }
 
 #[test]
+#[allow(clippy::map_collect_result_unit)]
 fn can_parse_all_emotes() -> Result<(), impl Error> {
     let data = [
         include_str!("../emote-221102-1.json"),
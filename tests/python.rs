@@ -0,0 +1,83 @@
+#![cfg(feature = "python")]
+
+use pyo3::{prelude::*, types::PyDict};
+
+const REPO: &str = r#"[
+    {
+        "id": 1,
+        "name": "Surprised",
+        "commands": ["/surprised"],
+        "en": {
+            "targeted": "<If(Equal(ObjectParameter(1),ObjectParameter(2)))>You look<Else/><If(PlayerParameter(7))><SheetEn(ObjStr,2,PlayerParameter(7),1,1)/><Else/>ObjectParameter(2)</If> looks</If> at <If(Equal(ObjectParameter(1),ObjectParameter(3)))>you<Else/><If(PlayerParameter(8))><SheetEn(ObjStr,2,PlayerParameter(8),1,1)/><Else/>ObjectParameter(3)</If></If> in surprise.",
+            "untargeted": "<If(Equal(ObjectParameter(1),ObjectParameter(2)))>You look<Else/><If(PlayerParameter(7))><SheetEn(ObjStr,2,PlayerParameter(7),1,1)/><Else/>ObjectParameter(2)</If> looks</If> surprised."
+        },
+        "ja": { "targeted": "<If(PlayerParameter(99))>a<Else/>b</If>", "untargeted": "ja untargeted" }
+    }
+]"#;
+
+/// Runs python code with the module imported as `xiv` and the repository json as `REPO`.
+fn run_python(code: &str) -> PyResult<()> {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let module = PyModule::new_bound(py, "xiv_emote_parser")?;
+        xiv_emote_parser::python::xiv_emote_parser(&module)?;
+        let globals = PyDict::new_bound(py);
+        globals.set_item("xiv", module)?;
+        globals.set_item("REPO", REPO)?;
+        py.run_bound(code, Some(&globals), None)
+    })
+}
+
+#[test]
+fn renders_emote() -> PyResult<()> {
+    run_python(
+        r#"
+repo = xiv.LogMessageRepository.from_json(REPO)
+assert "/surprised" in repo
+assert repo.emote_list() == ["/surprised"]
+alice = xiv.Character("Alice", xiv.Gender.Female, is_self=True)
+bob = xiv.Character("Bob", xiv.Gender.Male)
+assert bob.gender == xiv.Gender.Male and bob.is_pc and not bob.is_self
+answers = xiv.LogMessageAnswers(alice, bob)
+assert answers.target.name == "Bob"
+message = xiv.process_log_message(repo.targeted("/surprised", "en"), answers)
+assert message == "You look at Bob in surprise.", message
+answers = xiv.LogMessageAnswers(bob, alice)
+message = xiv.process_log_message(repo.untargeted("/surprised", "en"), answers)
+assert message == "Bob looks surprised.", message
+answers = xiv.LogMessageAnswers(alice)
+assert answers.target.name == "" and not answers.target.is_self
+message = xiv.process_log_message(repo.untargeted("/surprised", "en"), answers)
+assert message == "You look surprised.", message
+"#,
+    )
+}
+
+#[test]
+fn maps_errors_to_exceptions() -> PyResult<()> {
+    run_python(
+        r#"
+assert issubclass(xiv.NotFoundError, xiv.LogMessageRepositoryError)
+assert issubclass(xiv.ProcessError, xiv.EmoteTextError)
+repo = xiv.LogMessageRepository.from_json(REPO)
+
+def raises(exception, f):
+    try:
+        f()
+    except exception:
+        return
+    raise AssertionError(f"expected {exception.__name__}")
+
+raises(xiv.NotFoundError, lambda: repo.targeted("/unknown", "en"))
+raises(xiv.NoLogMessagesError, lambda: repo.targeted("/surprised", "ko"))
+raises(xiv.LogMessageRepositoryError, lambda: xiv.LogMessageRepository.from_json("{"))
+raises(ValueError, lambda: repo.targeted("/surprised", "xx"))
+alice = xiv.Character("Alice", xiv.Gender.Female, is_self=True)
+bob = xiv.Character("Bob", xiv.Gender.Male, is_self=True)
+raises(xiv.LogMessageAnswersError, lambda: xiv.LogMessageAnswers(alice, bob))
+answers = xiv.LogMessageAnswers(alice, xiv.Character("Bob", xiv.Gender.Male))
+raises(xiv.ProcessError, lambda: xiv.process_log_message(repo.targeted("/surprised", "ja"), answers))
+raises(xiv.ParseError, lambda: xiv.process_log_message("<If(", answers))
+"#,
+    )
+}
@@ -1,4 +1,15 @@
-use xiv_emote_parser::repository::{LogMessageRepository, LogMessageRepositoryError};
+#![cfg(feature = "xivapi")]
+#![allow(clippy::result_large_err)]
+
+mod common;
+
+use std::{sync::atomic::Ordering, time::Duration};
+
+use common::{serve, test_config, EMPTY_PAGE, SURPRISED_PAGE};
+use xiv_emote_parser::repository::{
+    Language, LogMessageRepository, LogMessageRepositoryError, MissingField, PartialEmote,
+    SharedLogMessageRepository, SkipReason, XivapiConfig,
+};
 
 #[test]
 fn can_load_from_xivapi() -> Result<(), LogMessageRepositoryError> {
@@ -8,3 +19,273 @@ fn can_load_from_xivapi() -> Result<(), LogMessageRepositoryError> {
     assert!(repo.emote_list().count() > 0);
     Ok(())
 }
+
+#[test]
+fn retries_on_server_errors() -> Result<(), LogMessageRepositoryError> {
+    let (url, count) = serve(vec![(503, ""), (429, ""), (200, EMPTY_PAGE)]);
+    let query = LogMessageRepository::prep_xivapi_query(None);
+    let results = LogMessageRepository::load_xivapi_with_config(&query, &test_config(url, 5))?;
+    assert_eq!(results.len(), 1);
+    assert_eq!(count.load(Ordering::SeqCst), 3);
+    Ok(())
+}
+
+#[test]
+fn request_limit_when_retries_exhausted() {
+    let (url, count) = serve(vec![(429, "")]);
+    let query = LogMessageRepository::prep_xivapi_query(None);
+    let res = LogMessageRepository::load_xivapi_with_config(&query, &test_config(url, 2));
+    assert!(
+        matches!(res, Err(LogMessageRepositoryError::RequestLimit)),
+        "expected request limit, got {:?}",
+        res
+    );
+    assert_eq!(count.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn client_errors_are_not_retried() {
+    let (url, count) = serve(vec![(404, "")]);
+    let query = LogMessageRepository::prep_xivapi_query(None);
+    let res = LogMessageRepository::load_xivapi_with_config(&query, &test_config(url, 2));
+    assert!(
+        matches!(res, Err(LogMessageRepositoryError::Network(_))),
+        "expected network error, got {:?}",
+        res
+    );
+    assert_eq!(count.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn backoff_is_exponential_and_capped() {
+    let config = XivapiConfig {
+        initial_backoff: Duration::from_millis(100),
+        max_backoff: Duration::from_millis(500),
+        ..XivapiConfig::default()
+    };
+    assert_eq!(config.backoff(0), Duration::from_millis(100));
+    assert_eq!(config.backoff(1), Duration::from_millis(200));
+    assert_eq!(config.backoff(2), Duration::from_millis(400));
+    assert_eq!(config.backoff(3), Duration::from_millis(500));
+    assert_eq!(config.backoff(40), Duration::from_millis(500));
+}
+
+#[test]
+fn shared_reload_keeps_snapshots() -> Result<(), LogMessageRepositoryError> {
+    let (url, _) = serve(vec![(200, EMPTY_PAGE), (200, SURPRISED_PAGE)]);
+    let shared = SharedLogMessageRepository::new(LogMessageRepository::from_xivapi_with_config(
+        None,
+        test_config(url, 0),
+    )?);
+    let before = shared.load();
+    shared
+        .reload_in_background()
+        .join()
+        .expect("reload thread panicked")?;
+    assert!(!before.contains_emote("/surprised"));
+    assert!(shared.load().contains_emote("/surprised"));
+    Ok(())
+}
+
+#[test]
+fn shared_failed_reload_keeps_current() -> Result<(), LogMessageRepositoryError> {
+    let (url, _) = serve(vec![(200, SURPRISED_PAGE), (404, "")]);
+    let shared = SharedLogMessageRepository::new(LogMessageRepository::from_xivapi_with_config(
+        None,
+        test_config(url, 0),
+    )?);
+    assert!(shared.reload().is_err());
+    assert!(shared.load().contains_emote("/surprised"));
+    Ok(())
+}
+
+const TEXT_COMMAND_PAGE: &str = r#"{"pagination":{"page_next":null},"results":[{"id":1,"name":"Surprised","name_en":"Surprised","name_ja":"おどろく","log_message_targeted":{"text_en":"a","text_ja":"b"},"log_message_untargeted":{"text_en":"c","text_ja":"d"},"text_command":{"id":402,"command_en":"/surprised","command_ja":"/おどろく","short_command_en":"/sp","short_command_ja":"","alias_en":"","alias_ja":"","short_alias_en":"","short_alias_ja":"/おど","description_en":"USAGE: /surprised","description_ja":"/おどろく サブコマンド"}}]}"#;
+
+#[test]
+fn loads_text_command_metadata() -> Result<(), LogMessageRepositoryError> {
+    let (url, _) = serve(vec![(200, TEXT_COMMAND_PAGE)]);
+    let repo = LogMessageRepository::from_xivapi_with_config(None, test_config(url, 0))?;
+    for command in ["/surprised", "/sp", "/おどろく", "/おど"] {
+        assert_eq!(repo.find_emote_id(command), Some(1), "{}", command);
+    }
+    let emote = repo.emote_by_id(1)?;
+    assert_eq!(
+        emote.commands,
+        vec!["/surprised", "/sp", "/おどろく", "/おど"]
+    );
+    assert_eq!(
+        emote.description_in(Language::En),
+        Some("USAGE: /surprised")
+    );
+    assert_eq!(
+        emote.description_in(Language::Ja),
+        Some("/おどろく サブコマンド")
+    );
+    assert_eq!(emote.name_in(Language::Ja), Some("おどろく"));
+    Ok(())
+}
+
+const CATEGORY_PAGE: &str = r#"{"pagination":{"page_next":null},"results":[{"id":1,"name":"Surprised","log_message_targeted":{"text_en":"a","text_ja":"b","log_kind":29},"log_message_untargeted":{"text_en":"c","text_ja":"d","log_kind":29},"text_command":{"command_en":"/surprised"},"emote_category":{"id":2,"name_en":"Expressions","name_ja":"感情表現"},"icon_id":64021,"unlock_link":0}]}"#;
+
+#[test]
+fn loads_category_icon_and_log_kind() -> Result<(), LogMessageRepositoryError> {
+    let (url, _) = serve(vec![(200, CATEGORY_PAGE)]);
+    let repo = LogMessageRepository::from_xivapi_with_config(None, test_config(url, 0))?;
+    let emote = repo.emote_by_id(1)?;
+    let category = emote.category.as_ref().expect("category should be loaded");
+    assert_eq!(category.id, 2);
+    assert_eq!(category.name_in(Language::Ja), Some("感情表現"));
+    assert_eq!(emote.icon, Some(64021));
+    assert_eq!(emote.log_kind, Some(29));
+    assert!(!emote.requires_unlock());
+    assert_eq!(repo.emotes_in_category(2).count(), 1);
+    Ok(())
+}
+
+const INCOMPLETE_PAGE: &str = r#"{"pagination":{"page_next":null},"results":[{"id":1,"name":"Surprised","log_message_targeted":{"text_en":"a","text_ja":"b"},"log_message_untargeted":{"text_en":"c","text_ja":"d"},"text_command":{"command_en":"/surprised"}},{"id":2,"name":"Sit","log_message_targeted":null,"log_message_untargeted":null,"text_command":{"command_en":"/sit"}},{"id":3,"name":"Unused","text_command":null},{"id":4,"name":"Half","log_message_targeted":{"text_en":"a","text_ja":"b"},"text_command":{"command_en":"/half"}},{"id":5}]}"#;
+
+#[test]
+fn reports_skipped_emotes() -> Result<(), LogMessageRepositoryError> {
+    let (url, _) = serve(vec![(200, INCOMPLETE_PAGE)]);
+    let repo = LogMessageRepository::from_xivapi_with_config(None, test_config(url, 0))?;
+    assert!(repo.contains_emote("/surprised"));
+    assert!(!repo.contains_emote("/sit"));
+    let report = repo.load_report();
+    let reasons: Vec<_> = report
+        .skipped
+        .iter()
+        .map(|skipped| (skipped.id, skipped.reason.clone()))
+        .collect();
+    assert_eq!(
+        reasons,
+        vec![
+            (
+                Some(2),
+                SkipReason::MissingLogMessages(vec![
+                    MissingField::LogMessageTargeted,
+                    MissingField::LogMessageUntargeted
+                ])
+            ),
+            (Some(3), SkipReason::MissingTextCommand),
+            (
+                Some(4),
+                SkipReason::MissingLogMessages(vec![MissingField::LogMessageUntargeted])
+            ),
+            (Some(5), SkipReason::MissingName),
+        ]
+    );
+    assert!(report.partial.is_empty());
+    Ok(())
+}
+
+#[test]
+fn can_include_motion_only_emotes() -> Result<(), LogMessageRepositoryError> {
+    let (url, _) = serve(vec![(200, INCOMPLETE_PAGE)]);
+    let config = XivapiConfig {
+        include_motion_only: true,
+        ..test_config(url, 0)
+    };
+    let repo = LogMessageRepository::from_xivapi_with_config(None, config)?;
+    let sit = repo.messages("/sit")?;
+    assert_eq!(sit.en, None);
+    assert!(matches!(
+        repo.targeted("/sit", Language::En),
+        Err(LogMessageRepositoryError::NoLogMessages)
+    ));
+    assert_eq!(repo.untargeted("/surprised", Language::Ja)?, "d");
+    // partial emotes keep the messages which loaded
+    assert_eq!(repo.targeted("/half", Language::En)?, "a");
+    assert_eq!(repo.targeted("/half", Language::Ja)?, "b");
+    assert!(matches!(
+        repo.untargeted("/half", Language::En),
+        Err(LogMessageRepositoryError::NoLogMessages)
+    ));
+    let report = repo.load_report();
+    assert_eq!(report.skipped.len(), 2);
+    assert_eq!(
+        report.partial[0],
+        PartialEmote {
+            id: 2,
+            name: "Sit".to_string(),
+            languages: vec![Language::En, Language::Ja],
+            missing: vec![
+                MissingField::LogMessageTargeted,
+                MissingField::LogMessageUntargeted
+            ],
+        }
+    );
+    assert_eq!(report.partial[1].id, 4);
+    Ok(())
+}
+
+const CHS_DUMP: &str = r#"[
+    { "id": 1, "commands": ["/惊讶"], "targeted": "chs targeted", "untargeted": "chs untargeted" },
+    { "id": 4, "commands": [], "targeted": "chs targeted" }
+]"#;
+
+#[test]
+fn language_dump_updates_report() -> Result<(), LogMessageRepositoryError> {
+    let (url, _) = serve(vec![(200, INCOMPLETE_PAGE)]);
+    let config = XivapiConfig {
+        include_motion_only: true,
+        ..test_config(url, 0)
+    };
+    let mut repo = LogMessageRepository::from_xivapi_with_config(None, config)?;
+    repo.load_language_dump(Language::Chs, CHS_DUMP)?;
+    let partial: Vec<_> = repo
+        .load_report()
+        .partial
+        .iter()
+        .map(|partial| {
+            (
+                partial.id,
+                partial.languages.clone(),
+                partial.missing.clone(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        partial,
+        vec![
+            (
+                2,
+                vec![Language::En, Language::Ja],
+                vec![
+                    MissingField::LogMessageTargeted,
+                    MissingField::LogMessageUntargeted
+                ]
+            ),
+            (
+                4,
+                vec![Language::En, Language::Ja],
+                vec![MissingField::LogMessageUntargeted]
+            ),
+            (
+                4,
+                vec![Language::Chs],
+                vec![MissingField::LogMessageUntargeted]
+            ),
+        ]
+    );
+    // loading the language again replaces its entries
+    repo.load_language_dump(Language::Chs, CHS_DUMP)?;
+    assert_eq!(repo.load_report().partial.len(), 3);
+    Ok(())
+}
+
+#[test]
+fn reload_keeps_language_dumps() -> Result<(), LogMessageRepositoryError> {
+    let (url, _) = serve(vec![(200, SURPRISED_PAGE)]);
+    let mut repo = LogMessageRepository::from_xivapi_with_config(None, test_config(url, 0))?;
+    repo.load_language_dump(Language::Chs, CHS_DUMP)?;
+    repo.reload_messages()?;
+    assert_eq!(repo.targeted("/惊讶", Language::Chs)?, "chs targeted");
+    assert_eq!(repo.untargeted("/surprised", Language::En)?, "c");
+    let shared = SharedLogMessageRepository::new(repo);
+    shared.reload()?;
+    assert_eq!(
+        shared.load().targeted("/surprised", Language::Chs)?,
+        "chs targeted"
+    );
+    Ok(())
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "xivapi-async")]
+#![allow(clippy::result_large_err)]
+
+mod common;
+
+use std::sync::atomic::Ordering;
+
+use common::{serve, test_config, EMPTY_PAGE, SURPRISED_PAGE};
+use xiv_emote_parser::repository::{
+    LogMessageRepository, LogMessageRepositoryError, SharedLogMessageRepository,
+};
+
+#[tokio::test]
+async fn async_retries_on_server_errors() -> Result<(), LogMessageRepositoryError> {
+    let (url, count) = serve(vec![(500, ""), (429, ""), (200, EMPTY_PAGE)]);
+    let query = LogMessageRepository::prep_xivapi_query(None);
+    let results =
+        LogMessageRepository::load_xivapi_async_with_config(&query, &test_config(url, 5)).await?;
+    assert_eq!(results.len(), 1);
+    assert_eq!(count.load(Ordering::SeqCst), 3);
+    Ok(())
+}
+
+#[tokio::test]
+async fn async_request_limit_when_retries_exhausted() {
+    let (url, count) = serve(vec![(503, "")]);
+    let query = LogMessageRepository::prep_xivapi_query(None);
+    let res =
+        LogMessageRepository::load_xivapi_async_with_config(&query, &test_config(url, 2)).await;
+    assert!(
+        matches!(res, Err(LogMessageRepositoryError::RequestLimit)),
+        "expected request limit, got {:?}",
+        res
+    );
+    assert_eq!(count.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn async_reload_replaces_messages() -> Result<(), LogMessageRepositoryError> {
+    let (url, _) = serve(vec![(200, EMPTY_PAGE), (200, SURPRISED_PAGE)]);
+    let mut repo =
+        LogMessageRepository::from_xivapi_async_with_config(None, test_config(url, 0)).await?;
+    assert_eq!(repo.emote_list().count(), 0);
+    repo.reload_messages_async().await?;
+    assert!(repo.contains_emote("/surprised"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn shared_async_reload() -> Result<(), LogMessageRepositoryError> {
+    let (url, _) = serve(vec![(200, EMPTY_PAGE), (200, SURPRISED_PAGE)]);
+    let shared = SharedLogMessageRepository::new(
+        LogMessageRepository::from_xivapi_async_with_config(None, test_config(url, 0)).await?,
+    );
+    let before = shared.load();
+    shared.reload_async().await?;
+    assert!(!before.contains_emote("/surprised"));
+    assert!(shared.load().contains_emote("/surprised"));
+    Ok(())
+}
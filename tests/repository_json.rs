@@ -0,0 +1,432 @@
+#![cfg(feature = "json")]
+#![allow(clippy::result_large_err)]
+
+mod common;
+
+use std::sync::Arc;
+
+use common::SURPRISED_PAGE;
+use xiv_emote_parser::repository::{
+    normalize_command, CommandCollision, EmoteData, EmoteView, Language, LogMessageRepository,
+    LogMessageRepositoryError, MatchKind, MessageChange, MessageKind, SkipReason,
+};
+
+const MESSAGES: &str = r#"[
+    {
+        "id": 1,
+        "name": "Surprised",
+        "names": { "Ja": "おどろく" },
+        "commands": ["/surprised", "/おどろく"],
+        "descriptions": { "En": "USAGE: /surprised" },
+        "category": { "id": 2, "names": { "En": "Expressions" } },
+        "icon": 64021,
+        "unlock_link": 0,
+        "log_kind": 29,
+        "en": { "targeted": "en targeted", "untargeted": "en untargeted" },
+        "ja": { "targeted": "ja targeted", "untargeted": "ja untargeted" }
+    },
+    {
+        "id": 2,
+        "name": "Angry",
+        "commands": ["/angry"],
+        "category": { "id": 1, "names": { "En": "General" } },
+        "unlock_link": 1153,
+        "log_kind": 29,
+        "en": { "targeted": "en targeted", "untargeted": "en untargeted" },
+        "ja": { "targeted": "ja targeted", "untargeted": "ja untargeted" }
+    },
+    {
+        "id": 3,
+        "name": "Annoyed",
+        "commands": ["/Annoyed", "annoy"],
+        "en": { "targeted": "en targeted", "untargeted": "en untargeted" },
+        "ja": { "targeted": "ja targeted", "untargeted": "ja untargeted" }
+    }
+]"#;
+
+#[test]
+fn can_lookup_by_id() -> Result<(), LogMessageRepositoryError> {
+    let repo = LogMessageRepository::from_json(MESSAGES)?;
+    assert_eq!(repo.emote_by_id(1)?.name, "Surprised");
+    assert_eq!(repo.emote_by_id(2)?.name, "Angry");
+    assert!(matches!(
+        repo.emote_by_id(4),
+        Err(LogMessageRepositoryError::NotFound)
+    ));
+    assert_eq!(repo.find_emote_id("/おどろく"), Some(1));
+    assert_eq!(repo.find_emote_id("/unknown"), None);
+    Ok(())
+}
+
+#[test]
+fn can_lookup_by_localized_name() -> Result<(), LogMessageRepositoryError> {
+    let repo = LogMessageRepository::from_json(MESSAGES)?;
+    assert_eq!(repo.emote_by_name("Surprised", Language::En)?.id, 1);
+    assert_eq!(repo.emote_by_name("おどろく", Language::Ja)?.id, 1);
+    // name falls back to en when no localized names are given
+    assert_eq!(repo.emote_by_name("Angry", Language::En)?.id, 2);
+    assert!(repo.emote_by_name("Angry", Language::Ja).is_err());
+    assert!(repo.emote_by_name("おどろく", Language::En).is_err());
+    assert_eq!(repo.emote_by_id(1)?.name_in(Language::Ja), Some("おどろく"));
+    Ok(())
+}
+
+#[test]
+fn can_complete_commands() -> Result<(), LogMessageRepositoryError> {
+    let repo = LogMessageRepository::from_json(MESSAGES)?;
+    assert_eq!(
+        repo.complete("/an").collect::<Vec<_>>(),
+        vec!["/angry", "/annoy", "/annoyed"]
+    );
+    assert_eq!(
+        repo.complete("/おど").collect::<Vec<_>>(),
+        vec!["/おどろく"]
+    );
+    assert_eq!(repo.complete("/x").count(), 0);
+    assert_eq!(repo.complete("").count(), 5);
+    Ok(())
+}
+
+#[test]
+fn can_search_with_typos() -> Result<(), LogMessageRepositoryError> {
+    let repo = LogMessageRepository::from_json(MESSAGES)?;
+
+    let results = repo.search("/suprised", 5);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].command, "/surprised");
+    assert_eq!(results[0].kind, MatchKind::Fuzzy);
+    assert_eq!(results[0].distance, 1);
+
+    // partially typed with a typo
+    let results = repo.search("/supris", 5);
+    assert_eq!(results[0].command, "/surprised");
+
+    let results = repo.search("/annoy", 5);
+    let ranked: Vec<_> = results.iter().map(|m| (m.command, m.kind)).collect();
+    assert_eq!(
+        ranked,
+        vec![
+            ("/annoy", MatchKind::Exact),
+            ("/annoyed", MatchKind::Prefix)
+        ]
+    );
+    assert_eq!(results[1].emote.id, 3);
+
+    // equally distant matches are ranked shortest first
+    let results = repo.search("/anoy", 5);
+    let ranked: Vec<_> = results.iter().map(|m| (m.command, m.distance)).collect();
+    assert_eq!(ranked, vec![("/annoy", 1), ("/annoyed", 1)]);
+
+    assert_eq!(repo.search("/annoy", 1).len(), 1);
+    assert!(repo.search("/dance", 5).is_empty());
+    Ok(())
+}
+
+#[test]
+fn normalizes_commands() {
+    assert_eq!(normalize_command("/surprised"), "/surprised");
+    assert_eq!(normalize_command("Surprised"), "/surprised");
+    assert_eq!(normalize_command(" /SURPRISED "), "/surprised");
+    assert_eq!(normalize_command("／ｓｕｒｐｒｉｓｅｄ"), "/surprised");
+    assert_eq!(normalize_command("//surprised"), "/surprised");
+    assert_eq!(normalize_command("／おどろく"), "/おどろく");
+}
+
+#[test]
+fn lookups_are_normalized() -> Result<(), LogMessageRepositoryError> {
+    let repo = LogMessageRepository::from_json(MESSAGES)?;
+    for command in [
+        "/surprised",
+        "/Surprised",
+        "surprised",
+        "／ＳＵＲＰＲＩＳＥＤ",
+        "おどろく",
+    ] {
+        assert_eq!(
+            repo.targeted(command, Language::En)?,
+            "en targeted",
+            "{}",
+            command
+        );
+        assert_eq!(repo.find_emote_id(command), Some(1), "{}", command);
+    }
+    // keys given in the json are normalized when indexing
+    assert!(repo.contains_emote("/annoyed"));
+    assert_eq!(repo.messages("/ANNOY")?.id, 3);
+    assert_eq!(
+        repo.complete("ANN").collect::<Vec<_>>(),
+        vec!["/annoy", "/annoyed"]
+    );
+    assert_eq!(repo.search("Suprised", 1)[0].command, "/surprised");
+    Ok(())
+}
+
+#[test]
+fn lists_commands_as_written() -> Result<(), LogMessageRepositoryError> {
+    let repo = LogMessageRepository::from_json(MESSAGES)?;
+    assert_eq!(
+        repo.emote_list_by_id().collect::<Vec<_>>(),
+        vec!["/surprised", "/おどろく", "/angry", "/Annoyed", "annoy"]
+    );
+    assert!(repo.command_collisions().is_empty());
+    Ok(())
+}
+
+#[test]
+fn reports_colliding_commands() -> Result<(), LogMessageRepositoryError> {
+    let repo = LogMessageRepository::from_json(
+        r#"[
+            { "id": 7, "name": "Annoyed Again", "commands": ["/annoyed", "/irked"] },
+            { "id": 3, "name": "Annoyed", "commands": ["/Annoyed"] },
+            { "id": 9, "name": "Irked", "commands": ["/IRKED"] }
+        ]"#,
+    )?;
+    assert_eq!(
+        repo.command_collisions(),
+        [
+            CommandCollision {
+                command: "/annoyed".to_string(),
+                kept: 3,
+                dropped: 7,
+            },
+            CommandCollision {
+                command: "/irked".to_string(),
+                kept: 7,
+                dropped: 9,
+            },
+        ]
+    );
+    assert_eq!(repo.messages("/annoyed")?.id, 3);
+    assert_eq!(repo.messages("/irked")?.id, 7);
+    let mut commands: Vec<_> = repo.emote_list().collect();
+    commands.sort_unstable();
+    assert_eq!(commands, vec!["/Annoyed", "/irked"]);
+    Ok(())
+}
+
+#[test]
+fn can_filter_emotes() -> Result<(), LogMessageRepositoryError> {
+    let repo = LogMessageRepository::from_json(MESSAGES)?;
+    assert_eq!(repo.emotes().count(), 3);
+    let categories: Vec<_> = repo
+        .categories()
+        .into_iter()
+        .map(|category| category.name_in(Language::En))
+        .collect();
+    assert_eq!(categories, vec![Some("General"), Some("Expressions")]);
+    let in_category: Vec<_> = repo.emotes_in_category(2).map(|data| data.id).collect();
+    assert_eq!(in_category, vec![1]);
+    assert_eq!(repo.emotes_with_log_kind(29).count(), 2);
+    let mut default: Vec<_> = repo.default_emotes().map(|data| data.id).collect();
+    default.sort_unstable();
+    assert_eq!(default, vec![1, 3]);
+    assert!(repo.emote_by_id(2)?.requires_unlock());
+    assert_eq!(repo.emote_by_id(1)?.icon, Some(64021));
+    Ok(())
+}
+
+#[test]
+fn motion_only_emotes_have_no_messages() -> Result<(), LogMessageRepositoryError> {
+    let repo = LogMessageRepository::from_json(
+        r#"[
+            { "id": 50, "name": "Sit", "commands": ["/sit"] },
+            {
+                "id": 2,
+                "name": "Angry",
+                "commands": ["/angry"],
+                "en": { "targeted": "en targeted", "untargeted": "en untargeted" },
+                "ja": { "targeted": "ja targeted", "untargeted": "ja untargeted" }
+            }
+        ]"#,
+    )?;
+    assert_eq!(repo.messages("/sit")?.messages_in(Language::En), None);
+    assert!(matches!(
+        repo.untargeted("/sit", Language::Ja),
+        Err(LogMessageRepositoryError::NoLogMessages)
+    ));
+    assert!(matches!(
+        repo.untargeted("/stand", Language::Ja),
+        Err(LogMessageRepositoryError::NotFound)
+    ));
+    assert_eq!(repo.targeted("/angry", Language::En)?, "en targeted");
+    Ok(())
+}
+
+#[test]
+fn can_diff_repositories() -> Result<(), LogMessageRepositoryError> {
+    let older = LogMessageRepository::from_json(MESSAGES)?;
+    let newer = LogMessageRepository::from_json(
+        r#"[
+            {
+                "id": 1,
+                "name": "Surprised",
+                "commands": ["/surprised", "/おどろく"],
+                "en": { "targeted": "en targeted", "untargeted": "en untargeted" },
+                "ja": { "targeted": "ja targeted", "untargeted": "ja untargeted" }
+            },
+            {
+                "id": 2,
+                "name": "Angry",
+                "commands": ["/angry", "/ANGER"],
+                "en": { "targeted": "en targeted, fixed", "untargeted": "en untargeted" }
+            },
+            { "id": 4, "name": "Sit", "commands": ["/sit"] }
+        ]"#,
+    )?;
+    assert!(older.diff(&older).is_empty());
+    let diff = older.diff(&newer);
+    let ids = |emotes: &[Arc<EmoteData>]| emotes.iter().map(|data| data.id).collect::<Vec<_>>();
+    assert_eq!(ids(&diff.added), vec![4]);
+    assert_eq!(ids(&diff.removed), vec![3]);
+    assert_eq!(diff.changed.len(), 1);
+    let angry = &diff.changed[0];
+    assert_eq!(angry.id, 2);
+    assert_eq!(angry.added_commands, vec!["/anger"]);
+    assert!(angry.removed_commands.is_empty());
+    assert_eq!(
+        angry.messages,
+        vec![
+            MessageChange {
+                language: Language::En,
+                kind: MessageKind::Targeted,
+                old: Some("en targeted".to_string()),
+                new: Some("en targeted, fixed".to_string()),
+            },
+            MessageChange {
+                language: Language::Ja,
+                kind: MessageKind::Targeted,
+                old: Some("ja targeted".to_string()),
+                new: None,
+            },
+            MessageChange {
+                language: Language::Ja,
+                kind: MessageKind::Untargeted,
+                old: Some("ja untargeted".to_string()),
+                new: None,
+            },
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn can_load_language_dump() -> Result<(), LogMessageRepositoryError> {
+    let mut repo = LogMessageRepository::from_json(MESSAGES)?;
+    repo.load_language_dump(
+        Language::Chs,
+        r#"[
+            {
+                "id": 1,
+                "name": "惊讶",
+                "commands": ["/惊讶"],
+                "description": "用法：/惊讶",
+                "targeted": "chs targeted",
+                "untargeted": "chs untargeted"
+            },
+            { "id": 99, "name": "新表情", "commands": ["/新表情"] }
+        ]"#,
+    )?;
+    assert_eq!(repo.find_emote_id("/惊讶"), Some(1));
+    assert_eq!(repo.find_emote_id("/surprised"), Some(1));
+    assert_eq!(repo.targeted("/惊讶", Language::Chs)?, "chs targeted");
+    assert_eq!(
+        repo.untargeted("/surprised", Language::En)?,
+        "en untargeted"
+    );
+    assert!(matches!(
+        repo.targeted("/surprised", Language::Ko),
+        Err(LogMessageRepositoryError::NoLogMessages)
+    ));
+    assert_eq!(repo.emote_by_name("惊讶", Language::Chs)?.id, 1);
+    assert_eq!(
+        repo.emote_by_id(1)?.description_in(Language::Chs),
+        Some("用法：/惊讶")
+    );
+    let added = repo.emote_by_id(99)?;
+    assert_eq!(added.en, None);
+    assert_eq!(added.name_in(Language::Chs), Some("新表情"));
+    assert_eq!(repo.emotes().count(), 4);
+    Ok(())
+}
+
+#[test]
+fn language_dump_keeps_existing_messages() -> Result<(), LogMessageRepositoryError> {
+    let mut repo = LogMessageRepository::from_json(MESSAGES)?;
+    repo.load_language_dump(
+        Language::En,
+        r#"[{ "id": 1, "commands": [], "untargeted": "new untargeted" }]"#,
+    )?;
+    assert_eq!(repo.targeted("/surprised", Language::En)?, "en targeted");
+    assert_eq!(
+        repo.untargeted("/surprised", Language::En)?,
+        "new untargeted"
+    );
+    Ok(())
+}
+
+const PASCAL_CASE_PAGE: &str = r#"{"Pagination":{"PageNext":null},"Results":[{"ID":1,"Name":"Surprised","IconID":64021,"EmoteCategory":{"ID":2,"Name_en":"Expressions"},"LogMessageTargeted":{"LogKind":29,"Text_en":"a","Text_ja":"b"},"LogMessageUntargeted":{"Text_en":"c","Text_ja":"d"},"TextCommand":{"Command_en":"/surprised","ShortCommand_en":"/sp"}}]}"#;
+
+#[test]
+fn can_load_from_xivapi_dump() -> Result<(), LogMessageRepositoryError> {
+    let repo = LogMessageRepository::from_xivapi_dump(&[PASCAL_CASE_PAGE, SURPRISED_PAGE])?;
+    let surprised = repo.messages("/sp")?;
+    assert_eq!(surprised.icon, Some(64021));
+    assert_eq!(surprised.log_kind, Some(29));
+    assert_eq!(repo.untargeted("/surprised", Language::Ja)?, "d");
+
+    // older dumps were saved without the ID column
+    let repo = LogMessageRepository::from_xivapi_dump(&[include_str!("../emote-221102-1.json")])?;
+    assert_eq!(repo.emotes().count(), 0);
+    assert!(repo
+        .load_report()
+        .skipped
+        .iter()
+        .all(|skipped| skipped.reason == SkipReason::MissingId));
+    Ok(())
+}
+
+#[test]
+fn serializes_emote_views() -> Result<(), LogMessageRepositoryError> {
+    let repo = LogMessageRepository::from_json(MESSAGES)?;
+    let data = repo.emote_by_id(1)?;
+    let view = serde_json::to_value(EmoteView::from(data.as_ref()))?;
+    assert_eq!(view["id"], 1);
+    assert_eq!(view["commands"], serde_json::json!(data.commands));
+    assert_eq!(view["messages"]["en"]["targeted"], "en targeted");
+    // languages without messages are left out
+    assert!(view["messages"].get("ko").is_none());
+    Ok(())
+}
+
+#[test]
+fn language_dump_keeps_existing_keys() -> Result<(), LogMessageRepositoryError> {
+    let surprised = LogMessageRepository::from_json(MESSAGES)?
+        .emote_by_id(1)?
+        .clone();
+    let mut repo =
+        LogMessageRepository::new([("shocked".to_string(), surprised)].into_iter().collect());
+    repo.load_language_dump(
+        Language::Chs,
+        r#"[{ "id": 1, "commands": ["/惊讶"], "targeted": "chs targeted" }]"#,
+    )?;
+    assert_eq!(repo.targeted("shocked", Language::Chs)?, "chs targeted");
+    assert_eq!(repo.targeted("/惊讶", Language::En)?, "en targeted");
+    Ok(())
+}
+
+#[test]
+fn language_dump_leaves_out_emotes_without_commands() -> Result<(), LogMessageRepositoryError> {
+    let mut repo = LogMessageRepository::from_json(MESSAGES)?;
+    let count = repo.emotes().count();
+    repo.load_language_dump(
+        Language::Chs,
+        r#"[{ "id": 99, "name": "新表情", "commands": [], "targeted": "chs targeted" }]"#,
+    )?;
+    assert_eq!(repo.emotes().count(), count);
+    assert!(matches!(
+        repo.emote_by_id(99),
+        Err(LogMessageRepositoryError::NotFound)
+    ));
+    assert!(repo.load_report().partial.is_empty());
+    Ok(())
+}
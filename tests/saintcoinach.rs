@@ -0,0 +1,169 @@
+#![cfg(feature = "csv")]
+#![allow(clippy::result_large_err)]
+
+use std::collections::HashMap;
+
+use xiv_emote_parser::repository::{
+    CsvSheets, Language, LogMessageRepository, LogMessageRepositoryError,
+};
+
+const EMOTE_EN: &str = r#"key,0,1,2,3,4,5,6
+#,Name,EmoteCategory,TextCommand,Icon,LogMessage{Targeted},LogMessage{Untargeted},UnlockLink
+int32,str,EmoteCategory,TextCommand,Image,LogMessage,LogMessage,uint16
+0,"",0,0,0,0,0,0
+1,"Surprised",2,402,64021,501,502,0
+50,"Sit",1,403,64050,0,0,0
+60,"Unused",0,0,0,0,0,1153
+"#;
+
+const LOG_MESSAGE_EN: &str = r#"key,0,1,2
+#,LogKind,Args,Text
+int32,uint16,byte,str
+0,0,0,""
+501,29,0,"<If(Equal(ObjectParameter(1),ObjectParameter(2)))>you<Else/>ObjectParameter(2)</If> look at <If(Equal(ObjectParameter(1),ObjectParameter(3)))>you<Else/>ObjectParameter(3)</If> in surprise."
+502,29,0,"<If(Equal(ObjectParameter(1),ObjectParameter(2)))>you<Else/>ObjectParameter(2)</If>, surprised."
+"#;
+
+const TEXT_COMMAND_EN: &str = r#"key,0,1,2,3,4
+#,Command,ShortCommand,Alias,ShortAlias,Description
+int32,str,str,str,str,str
+402,"/surprised","/sp","","","USAGE: /surprised"
+403,"/sit","","","",""
+"#;
+
+const EMOTE_CATEGORY_EN: &str = r#"key,0
+#,Name
+int32,str
+1,"General"
+2,"Expressions"
+"#;
+
+const EMOTE_JA: &str = r#"key,0,1,2,3,4,5,6
+#,Name,EmoteCategory,TextCommand,Icon,LogMessage{Targeted},LogMessage{Untargeted},UnlockLink
+int32,str,EmoteCategory,TextCommand,Image,LogMessage,LogMessage,uint16
+1,"おどろく",2,402,64021,501,502,0
+50,"座る",1,403,64050,0,0,0
+"#;
+
+const LOG_MESSAGE_JA: &str = r#"key,0,1,2
+#,LogKind,Args,Text
+int32,uint16,byte,str
+501,29,0,"<If(Equal(ObjectParameter(1),ObjectParameter(2)))>you<Else/>ObjectParameter(2)</If>はおどろいた。"
+502,29,0,"<If(Equal(ObjectParameter(1),ObjectParameter(2)))>you<Else/>ObjectParameter(2)</If>はおどろいた。"
+"#;
+
+const TEXT_COMMAND_JA: &str = r#"key,0,1,2,3,4
+#,Command,ShortCommand,Alias,ShortAlias,Description
+int32,str,str,str,str,str
+402,"/おどろく","","","/おど","/おどろく"
+403,"/すわる","","","",""
+"#;
+
+fn sheets() -> HashMap<Language, CsvSheets> {
+    HashMap::from([
+        (
+            Language::En,
+            CsvSheets {
+                emote: EMOTE_EN.to_string(),
+                log_message: LOG_MESSAGE_EN.to_string(),
+                text_command: TEXT_COMMAND_EN.to_string(),
+                emote_category: Some(EMOTE_CATEGORY_EN.to_string()),
+            },
+        ),
+        (
+            Language::Ja,
+            CsvSheets {
+                emote: EMOTE_JA.to_string(),
+                log_message: LOG_MESSAGE_JA.to_string(),
+                text_command: TEXT_COMMAND_JA.to_string(),
+                emote_category: None,
+            },
+        ),
+    ])
+}
+
+#[test]
+fn can_join_sheets() -> Result<(), LogMessageRepositoryError> {
+    let repo = LogMessageRepository::from_csv(&sheets())?;
+    let surprised = repo.emote_by_id(1)?;
+    assert_eq!(surprised.name, "Surprised");
+    assert_eq!(surprised.name_in(Language::Ja), Some("おどろく"));
+    assert_eq!(
+        surprised.commands,
+        vec!["/surprised", "/sp", "/おどろく", "/おど"]
+    );
+    assert_eq!(
+        surprised.description_in(Language::En),
+        Some("USAGE: /surprised")
+    );
+    assert_eq!(surprised.icon, Some(64021));
+    assert_eq!(surprised.log_kind, Some(29));
+    assert!(!surprised.requires_unlock());
+    let category = surprised
+        .category
+        .as_ref()
+        .expect("category should be loaded");
+    assert_eq!(
+        (category.id, category.name_in(Language::En)),
+        (2, Some("Expressions"))
+    );
+    assert_eq!(
+        repo.untargeted("/おど", Language::En)?,
+        "<If(Equal(ObjectParameter(1),ObjectParameter(2)))>you<Else/>ObjectParameter(2)</If>, surprised."
+    );
+    assert!(repo
+        .targeted("/sp", Language::Ja)?
+        .ends_with("はおどろいた。"));
+    assert!(repo.validate().is_ok());
+    Ok(())
+}
+
+#[test]
+fn keeps_motion_only_and_skips_unusable_rows() -> Result<(), LogMessageRepositoryError> {
+    let repo = LogMessageRepository::from_csv(&sheets())?;
+    let sit = repo.messages("/すわる")?;
+    assert_eq!(sit.id, 50);
+    assert_eq!(sit.en, None);
+    // no text command
+    assert!(repo.emote_by_id(60).is_err());
+    // no name
+    assert!(repo.emote_by_id(0).is_err());
+    assert_eq!(repo.emotes().count(), 2);
+    Ok(())
+}
+
+#[test]
+fn can_load_from_dir() -> Result<(), LogMessageRepositoryError> {
+    let dir = std::env::temp_dir().join(format!("xiv-emote-csv-{}", std::process::id()));
+    for (language, sheets) in sheets() {
+        let dir = dir.join(language.code());
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(dir.join("Emote.csv"), sheets.emote)?;
+        std::fs::write(dir.join("LogMessage.csv"), sheets.log_message)?;
+        std::fs::write(dir.join("TextCommand.csv"), sheets.text_command)?;
+        if let Some(emote_category) = sheets.emote_category {
+            std::fs::write(dir.join("EmoteCategory.csv"), emote_category)?;
+        }
+    }
+    let repo = LogMessageRepository::from_csv_dir(&dir);
+    std::fs::remove_dir_all(&dir)?;
+    assert_eq!(repo?.find_emote_id("/おどろく"), Some(1));
+    Ok(())
+}
+
+#[test]
+fn missing_columns_are_reported() {
+    let mut sheets = sheets();
+    sheets.get_mut(&Language::En).unwrap().text_command =
+        "key,0\n#,Command\nint32,str\n".to_string();
+    let res = LogMessageRepository::from_csv(&sheets);
+    assert!(
+        matches!(
+            &res,
+            Err(LogMessageRepositoryError::InvalidSheet { sheet: "TextCommand", reason })
+                if reason.contains("ShortCommand")
+        ),
+        "expected invalid csv input, got {:?}",
+        res
+    );
+}
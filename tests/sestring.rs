@@ -0,0 +1,366 @@
+#![allow(clippy::result_large_err)]
+
+use xiv_emote_parser::{
+    log_message::{
+        condition::{Character, Gender, LogMessageAnswers},
+        parser::{decode_message, parse_message},
+        process_log_message, process_log_message_to_sestring, process_sestring_log_message,
+        EmoteTextError, PlayerWorlds, SeStringDecodeError, SeStringEncodeError,
+    },
+    sestring::{
+        code, decode_integer, encode_integer, expr, to_macro_text, Expression, Macro, SeString,
+        SeStringError, SeStringPart,
+    },
+};
+
+#[test]
+fn can_round_trip_integers() {
+    for value in [
+        0,
+        1,
+        0xCE,
+        0xCF,
+        0xFF,
+        0x100,
+        0x1234,
+        0xFF_0000,
+        0x0102_0304,
+        u32::MAX,
+    ] {
+        assert_eq!(
+            decode_integer(&encode_integer(value)),
+            Some(value),
+            "{}",
+            value
+        );
+    }
+    assert_eq!(encode_integer(5), vec![0x06]);
+    assert_eq!(encode_integer(0x0100), vec![0xF1, 0x01]);
+    assert_eq!(decode_integer(&[0xF2, 0x02, 0x03]), Some(0x0203));
+    assert_eq!(decode_integer(&[0xF2, 0x02]), None);
+}
+
+#[test]
+fn can_decode_if() -> Result<(), SeStringError> {
+    let bytes = [
+        &[0x02, code::IF, 0x0D, expr::EQUAL, 0xEB, 0x02, 0xEB, 0x03][..],
+        &[expr::STRING, 0x04, b'y', b'o', b'u', 0xEB, 0x03, 0x03],
+        b" waved.",
+    ]
+    .concat();
+    let string = SeString::decode(&bytes)?;
+    assert_eq!(
+        string.0[0],
+        SeStringPart::Macro(Macro {
+            code: code::IF,
+            args: vec![
+                Expression::Binary(
+                    expr::EQUAL,
+                    Box::new(Expression::Parameter(
+                        expr::OBJECT_PARAMETER,
+                        Box::new(Expression::Integer(1))
+                    )),
+                    Box::new(Expression::Parameter(
+                        expr::OBJECT_PARAMETER,
+                        Box::new(Expression::Integer(2))
+                    )),
+                ),
+                Expression::String(SeString(vec![SeStringPart::Text("you".to_string())])),
+                Expression::Parameter(expr::OBJECT_PARAMETER, Box::new(Expression::Integer(2))),
+            ],
+        })
+    );
+    assert_eq!(
+        string.to_macro_text(),
+        "<If(Equal(ObjectParameter(1),ObjectParameter(2)))>you<Else/>ObjectParameter(2)</If> waved."
+    );
+    Ok(())
+}
+
+#[test]
+fn can_decode_other_macros() -> Result<(), SeStringError> {
+    let bytes = [
+        &[0x02, code::UI_FOREGROUND, 0x04, 0xF2, 0x01, 0xF4, 0x03][..],
+        b"red",
+        &[0x02, code::UI_FOREGROUND, 0x02, expr::STACK_COLOR, 0x03],
+        &[0x02, code::NEW_LINE, 0x01, 0x03],
+        &[0x02, 0x7F, 0x02, 0x02, 0x03],
+    ]
+    .concat();
+    assert_eq!(
+        to_macro_text(&bytes)?,
+        "<UIForeground>F201F4</UIForeground>red<UIForeground>EC</UIForeground><br><Macro7F(1)/>"
+    );
+    Ok(())
+}
+
+#[test]
+fn invalid_strings_are_reported() {
+    assert_eq!(
+        SeString::decode(&[0x02, code::IF, 0x05, 0xE4]),
+        Err(SeStringError::UnexpectedEnd)
+    );
+    assert_eq!(
+        SeString::decode(&[0x02, code::NEW_LINE, 0x01, 0x00]),
+        Err(SeStringError::MissingPayloadEnd)
+    );
+    assert_eq!(
+        SeString::decode(&[0x02, 0x20, 0x02, 0xD0, 0x03]),
+        Err(SeStringError::UnknownExpression(0xD0))
+    );
+}
+
+fn payload(code: u8, args: &[Vec<u8>]) -> Vec<u8> {
+    let args = args.concat();
+    [
+        vec![0x02, code],
+        encode_integer(args.len() as u32),
+        args,
+        vec![0x03],
+    ]
+    .concat()
+}
+
+fn string(bytes: &[u8]) -> Vec<u8> {
+    [
+        vec![expr::STRING],
+        encode_integer(bytes.len() as u32),
+        bytes.to_vec(),
+    ]
+    .concat()
+}
+
+fn int(value: u32) -> Vec<u8> {
+    encode_integer(value)
+}
+
+fn param(code: u8, index: u32) -> Vec<u8> {
+    [vec![code], int(index)].concat()
+}
+
+fn if_else(cond: Vec<u8>, then: Vec<u8>, otherwise: Vec<u8>) -> Vec<u8> {
+    payload(code::IF, &[cond, then, otherwise])
+}
+
+/// `Equal(ObjectParameter(1),ObjectParameter(n))`, whether the origin or target is yourself
+fn is_self(n: u32) -> Vec<u8> {
+    [
+        vec![expr::EQUAL],
+        param(expr::OBJECT_PARAMETER, 1),
+        param(expr::OBJECT_PARAMETER, n),
+    ]
+    .concat()
+}
+
+/// `<If(PlayerParameter(n))><SheetEn(ObjStr,2,PlayerParameter(n),1,1)/><Else/>ObjectParameter(n - 5)</If>`
+fn name_en(n: u32) -> Vec<u8> {
+    let sheet = payload(
+        code::SHEET_EN,
+        &[
+            string(b"ObjStr"),
+            int(2),
+            param(expr::PLAYER_PARAMETER, n),
+            int(1),
+            int(1),
+        ],
+    );
+    if_else(
+        param(expr::PLAYER_PARAMETER, n),
+        string(&sheet),
+        param(expr::OBJECT_PARAMETER, n - 5),
+    )
+}
+
+#[test]
+fn can_decode_message() -> Result<(), EmoteTextError> {
+    let log_msg = "<Clickable(<If(Equal(ObjectParameter(1),ObjectParameter(2)))>your<Else/><If(Equal(ObjectParameter(1),ObjectParameter(2)))>you<Else/><If(PlayerParameter(7))><SheetEn(ObjStr,2,PlayerParameter(7),1,1)/><Else/>ObjectParameter(2)</If></If>'s</If>)/> eyes brim over with tears.";
+    let whose = if_else(is_self(2), string(b"you"), string(&name_en(7)));
+    let bytes = [
+        payload(
+            code::CLICKABLE,
+            &[string(&if_else(
+                is_self(2),
+                string(b"your"),
+                string(&[whose, b"'s".to_vec()].concat()),
+            ))],
+        ),
+        b" eyes brim over with tears.".to_vec(),
+    ]
+    .concat();
+    assert_eq!(to_macro_text(&bytes).unwrap(), log_msg);
+    assert_eq!(decode_message(&bytes)?, parse_message(log_msg)?);
+
+    let origin = Character::new("K'haldru Alaba", Gender::Female, true, true);
+    let target = Character::new("Puruo Jelly", Gender::Male, true, false);
+    let answers = LogMessageAnswers::new(target, origin).unwrap();
+    assert_eq!(
+        process_sestring_log_message(&bytes, &answers)?,
+        "Puruo Jelly's eyes brim over with tears."
+    );
+    Ok(())
+}
+
+#[test]
+fn can_decode_sheet_condition() -> Result<(), EmoteTextError> {
+    let log_msg = "<If(PlayerParameter(7))><If(<Sheet(BNpcName,PlayerParameter(7),6)/>)>her<Else/>his</If><Else/><If(PlayerParameter(5))>her<Else/>his</If></If> hands";
+    let gender = payload(
+        code::SHEET,
+        &[
+            string(b"BNpcName"),
+            param(expr::PLAYER_PARAMETER, 7),
+            int(6),
+        ],
+    );
+    let bytes = [
+        if_else(
+            param(expr::PLAYER_PARAMETER, 7),
+            string(&if_else(string(&gender), string(b"her"), string(b"his"))),
+            string(&if_else(
+                param(expr::PLAYER_PARAMETER, 5),
+                string(b"her"),
+                string(b"his"),
+            )),
+        ),
+        b" hands".to_vec(),
+    ]
+    .concat();
+    assert_eq!(decode_message(&bytes)?, parse_message(log_msg)?);
+
+    let origin = Character::new("K'haldru Alaba", Gender::Female, true, true);
+    let target = Character::new("Puruo Jelly", Gender::Male, true, false);
+    let answers = LogMessageAnswers::new(origin, target).unwrap();
+    assert_eq!(
+        process_sestring_log_message(&bytes, &answers)?,
+        process_log_message(log_msg, &answers)?
+    );
+    Ok(())
+}
+
+#[test]
+fn switches_become_nested_ifs() -> Result<(), EmoteTextError> {
+    let bytes = payload(
+        code::SWITCH,
+        &[
+            param(expr::PLAYER_PARAMETER, 4),
+            string(b"a"),
+            string(b"b"),
+            string(b"c"),
+        ],
+    );
+    assert_eq!(
+        decode_message(&bytes)?,
+        parse_message("<If(Equal(PlayerParameter(4),1))>a<Else/><If(Equal(PlayerParameter(4),2))>b<Else/>c</If></If>")?
+    );
+    Ok(())
+}
+
+#[test]
+fn unsupported_payloads_are_reported() {
+    let color = payload(code::UI_FOREGROUND, &[int(500)]);
+    assert!(matches!(
+        decode_message(&color),
+        Err(EmoteTextError::DecodeError(
+            SeStringDecodeError::UnsupportedMacro(code::UI_FOREGROUND)
+        ))
+    ));
+    let integer_branch = if_else(param(expr::PLAYER_PARAMETER, 7), int(1), string(b"a"));
+    assert!(matches!(
+        decode_message(&integer_branch),
+        Err(EmoteTextError::DecodeError(
+            SeStringDecodeError::UnsupportedExpression(Expression::Integer(1))
+        ))
+    ));
+    assert!(matches!(
+        decode_message(&color[..3]),
+        Err(EmoteTextError::DecodeError(SeStringDecodeError::SeString(
+            SeStringError::UnexpectedEnd
+        )))
+    ));
+}
+
+fn fixture_messages() -> Vec<String> {
+    [
+        include_str!("../emote-221102-1.json"),
+        include_str!("../emote-221102-2.json"),
+        include_str!("../emote-221102-3.json"),
+    ]
+    .into_iter()
+    .flat_map(|d| {
+        let v: serde_json::Value = serde_json::from_str(d).expect("couldn't parse test json");
+        v["Results"]
+            .as_array()
+            .cloned()
+            .expect("test json didn't contain Results array")
+    })
+    .flat_map(|emote| {
+        ["LogMessageTargeted", "LogMessageUntargeted"]
+            .into_iter()
+            .flat_map(|kind| ["Text_en", "Text_ja"].map(|lang| emote[kind][lang].clone()))
+            .filter_map(|v| v.as_str().map(String::from))
+            .collect::<Vec<_>>()
+    })
+    .collect()
+}
+
+#[test]
+fn can_round_trip_all_messages() -> Result<(), Box<dyn std::error::Error>> {
+    let messages = fixture_messages();
+    assert!(!messages.is_empty());
+    let origin = Character::new("K'haldru Alaba", Gender::Female, true, true);
+    let target = Character::new("Nanamo Ul Namo", Gender::Female, false, false);
+    let answers = LogMessageAnswers::new(origin, target)?;
+    for log_msg in messages {
+        let message = parse_message(&log_msg)?;
+        assert_eq!(message.to_string(), log_msg);
+        let bytes = message.encode()?;
+        assert_eq!(decode_message(&bytes)?, message, "{}", log_msg);
+        assert_eq!(to_macro_text(&bytes)?, log_msg);
+        assert_eq!(
+            process_sestring_log_message(&bytes, &answers)?,
+            process_log_message(&log_msg, &answers)?
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn functions_within_text_are_not_encoded() -> Result<(), EmoteTextError> {
+    let message =
+        parse_message("<If(PlayerParameter(7))>you<Else/>ObjectParameter(2)'s</If> friend")?;
+    assert!(matches!(
+        message.encode(),
+        Err(SeStringEncodeError::FunctionInText(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn can_encode_rendered_message() -> Result<(), Box<dyn std::error::Error>> {
+    let log_msg = "<Clickable(<If(Equal(ObjectParameter(1),ObjectParameter(2)))>you<Else/><If(PlayerParameter(7))><SheetEn(ObjStr,2,PlayerParameter(7),1,1)/><Else/>ObjectParameter(2)</If></If>)/> <If(Equal(ObjectParameter(1),ObjectParameter(2)))>look<Else/>looks</If> at <If(Equal(ObjectParameter(1),ObjectParameter(3)))><If(PlayerParameter(8))><SheetEn(ObjStr,2,PlayerParameter(8),1,1)/><Else/>you</If><Else/><If(PlayerParameter(8))><SheetEn(ObjStr,2,PlayerParameter(8),1,1)/><Else/>ObjectParameter(3)</If></If> in surprise.";
+    let origin = Character::new("Puruo Jelly", Gender::Male, true, false);
+    let target = Character::new("Nanamo Ul Namo", Gender::Female, false, false);
+    let answers = LogMessageAnswers::new(origin, target)?;
+    let worlds = PlayerWorlds {
+        origin: 73,
+        target: 0,
+    };
+    let bytes = process_log_message_to_sestring(log_msg, &answers, worlds)?;
+    let string = SeString::decode(&bytes)?;
+    assert_eq!(string, {
+        let mut parts = SeString::player_name("Puruo Jelly", 73).0;
+        parts.push(SeStringPart::Text(
+            " looks at Nanamo Ul Namo in surprise.".to_string(),
+        ));
+        SeString(parts)
+    });
+    assert_eq!(string.encode(), bytes);
+    // the link payload as sent by the game
+    assert_eq!(
+        &bytes[..20],
+        &[
+            0x02, 0x27, 0x12, 0x01, 0x01, 0x4A, 0x01, 0xFF, 0x0C, b'P', b'u', b'r', b'u', b'o',
+            b' ', b'J', b'e', b'l', b'l', b'y'
+        ]
+    );
+    Ok(())
+}
@@ -0,0 +1,523 @@
+#![cfg(feature = "sqpack")]
+#![allow(clippy::result_large_err)]
+
+use std::{io::Write, path::Path};
+
+use flate2::{write::DeflateEncoder, Compression};
+use xiv_emote_parser::{
+    repository::{Language, LogMessageRepository, LogMessageRepositoryError, SheetColumns},
+    sqpack::{
+        excel::{ExcelLanguage, ExcelSheet, Value},
+        index_key, SqPack, SqPackError,
+    },
+};
+
+/// Blocks are kept small so that files span several of them.
+const BLOCK_SIZE: usize = 64;
+const ALIGNMENT: usize = 0x80;
+
+enum Cell {
+    Str(Vec<u8>),
+    U16(u16),
+    U32(u32),
+}
+
+use Cell::*;
+
+fn text(s: &str) -> Cell {
+    Str(s.as_bytes().to_vec())
+}
+
+type Row = (u32, Vec<Cell>);
+
+struct Sheet {
+    name: &'static str,
+    /// the first row id of each page
+    pages: Vec<u32>,
+    /// the rows in each language, where the column types are taken from the first row
+    languages: Vec<(ExcelLanguage, Vec<Row>)>,
+}
+
+fn pad(bytes: &mut Vec<u8>, alignment: usize) {
+    bytes.resize(bytes.len().div_ceil(alignment) * alignment, 0);
+}
+
+fn exd(rows: &[&Row]) -> Vec<u8> {
+    let index_size = rows.len() * 8;
+    let mut index = Vec::new();
+    let mut data = Vec::new();
+    for (id, cells) in rows {
+        let mut fixed = Vec::new();
+        let mut strings = Vec::new();
+        for cell in cells {
+            match cell {
+                Str(s) => {
+                    fixed.extend((strings.len() as u32).to_be_bytes());
+                    strings.extend(s);
+                    strings.push(0);
+                }
+                U16(v) => fixed.extend(v.to_be_bytes()),
+                U32(v) => fixed.extend(v.to_be_bytes()),
+            }
+        }
+        index.extend(id.to_be_bytes());
+        index.extend(((0x20 + index_size + data.len()) as u32).to_be_bytes());
+        data.extend(((fixed.len() + strings.len()) as u32).to_be_bytes());
+        data.extend(1u16.to_be_bytes());
+        data.extend(fixed);
+        data.extend(strings);
+    }
+    let mut exd = b"EXDF".to_vec();
+    exd.extend(2u16.to_be_bytes());
+    exd.extend([0, 0]);
+    exd.extend((index_size as u32).to_be_bytes());
+    exd.extend((data.len() as u32).to_be_bytes());
+    exd.resize(0x20, 0);
+    exd.extend(index);
+    exd.extend(data);
+    exd
+}
+
+impl Sheet {
+    /// The paths and contents of the sheet's exh file and of the exd file for every page and
+    /// language.
+    fn files(&self) -> Vec<(String, Vec<u8>)> {
+        let (_, first_rows) = &self.languages[0];
+        let mut data_offset = 0u16;
+        let columns: Vec<_> = first_rows[0]
+            .1
+            .iter()
+            .map(|cell| {
+                let (kind, size) = match cell {
+                    Str(_) => (0x0u16, 4),
+                    U16(_) => (0x5, 2),
+                    U32(_) => (0x7, 4),
+                };
+                data_offset += size;
+                (kind, data_offset - size)
+            })
+            .collect();
+        let page_of = |id: u32| self.pages.iter().rposition(|&start| start <= id).unwrap();
+
+        let mut exh = b"EXHF".to_vec();
+        exh.extend(3u16.to_be_bytes());
+        exh.extend(data_offset.to_be_bytes());
+        exh.extend((columns.len() as u16).to_be_bytes());
+        exh.extend((self.pages.len() as u16).to_be_bytes());
+        exh.extend((self.languages.len() as u16).to_be_bytes());
+        exh.resize(0x20, 0);
+        for (kind, offset) in &columns {
+            exh.extend(kind.to_be_bytes());
+            exh.extend(offset.to_be_bytes());
+        }
+        for (page, start) in self.pages.iter().enumerate() {
+            let row_count = first_rows.iter().filter(|(id, _)| page_of(*id) == page);
+            exh.extend(start.to_be_bytes());
+            exh.extend((row_count.count() as u32).to_be_bytes());
+        }
+        for (language, _) in &self.languages {
+            exh.extend([language.id(), 0]);
+        }
+
+        let mut files = vec![(format!("exd/{}.exh", self.name.to_lowercase()), exh)];
+        for (language, rows) in &self.languages {
+            for (page, start) in self.pages.iter().enumerate() {
+                let rows: Vec<_> = rows.iter().filter(|(id, _)| page_of(*id) == page).collect();
+                let path = match language.suffix() {
+                    Some(suffix) => format!("exd/{}_{}_{}.exd", self.name, start, suffix),
+                    None => format!("exd/{}_{}.exd", self.name, start),
+                };
+                files.push((path.to_lowercase(), exd(&rows)));
+            }
+        }
+        files
+    }
+}
+
+/// Writes a standard file entry to the dat, compressing its blocks or storing them raw, and
+/// returns its offset.
+fn write_file(dat: &mut Vec<u8>, contents: &[u8], compress: bool) -> usize {
+    pad(dat, ALIGNMENT);
+    let offset = dat.len();
+    let chunks: Vec<_> = contents.chunks(BLOCK_SIZE).collect();
+    let mut entries = Vec::new();
+    let mut blocks = Vec::new();
+    for chunk in &chunks {
+        let (compressed_size, data) = if compress {
+            let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(chunk).unwrap();
+            let data = encoder.finish().unwrap();
+            (data.len() as u32, data)
+        } else {
+            (32000, chunk.to_vec())
+        };
+        let mut block = Vec::new();
+        block.extend(16u32.to_le_bytes());
+        block.extend(0u32.to_le_bytes());
+        block.extend(compressed_size.to_le_bytes());
+        block.extend((chunk.len() as u32).to_le_bytes());
+        block.extend(data);
+        pad(&mut block, ALIGNMENT);
+        entries.extend((blocks.len() as u32).to_le_bytes());
+        entries.extend((block.len() as u16).to_le_bytes());
+        entries.extend((chunk.len() as u16).to_le_bytes());
+        blocks.extend(block);
+    }
+    let header_size = (24 + entries.len()).div_ceil(ALIGNMENT) * ALIGNMENT;
+    dat.extend((header_size as u32).to_le_bytes());
+    dat.extend(2u32.to_le_bytes());
+    dat.extend((contents.len() as u32).to_le_bytes());
+    dat.extend([0; 8]);
+    dat.extend((chunks.len() as u32).to_le_bytes());
+    dat.extend(entries);
+    dat.resize(offset + header_size, 0);
+    dat.extend(blocks);
+    offset
+}
+
+/// Writes `sqpack/ffxiv/0a0000.win32.index` and `.dat0` holding the files of the sheets, with
+/// headers stored raw and data files compressed.
+fn write_sqpack(game_dir: &Path, sheets: &[Sheet]) -> std::io::Result<()> {
+    let mut dat = b"SqPack".to_vec();
+    let mut entries = Vec::new();
+    for (path, contents) in sheets.iter().flat_map(Sheet::files) {
+        let offset = write_file(&mut dat, &contents, path.ends_with(".exd"));
+        entries.extend(index_key(&path).to_le_bytes());
+        entries.extend(((offset / 8) as u32).to_le_bytes());
+        entries.extend(0u32.to_le_bytes());
+    }
+    let header_size = 0x400u32;
+    let mut index = b"SqPack".to_vec();
+    index.resize(0x0C, 0);
+    index.extend(header_size.to_le_bytes());
+    index.resize(header_size as usize, 0);
+    index.extend(header_size.to_le_bytes());
+    index.extend(0u32.to_le_bytes());
+    index.extend((header_size * 2).to_le_bytes());
+    index.extend((entries.len() as u32).to_le_bytes());
+    index.resize(header_size as usize * 2, 0);
+    index.extend(entries);
+
+    let dir = game_dir.join("sqpack/ffxiv");
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join("0a0000.win32.index"), index)?;
+    std::fs::write(dir.join("0a0000.win32.dat0"), dat)
+}
+
+/// `<If(Equal(ObjectParameter(1),ObjectParameter(2)))>you<Else/>ObjectParameter(2)</If>`
+const IF_YOU: &[u8] = &[
+    0x02, 0x08, 0x0D, 0xE4, 0xEB, 0x02, 0xEB, 0x03, 0xFF, 0x04, b'y', b'o', b'u', 0xEB, 0x03, 0x03,
+];
+
+fn if_you(rest: &str) -> Cell {
+    Str([IF_YOU, rest.as_bytes()].concat())
+}
+
+fn emote_rows(surprised: &str, sit: &str) -> Vec<Row> {
+    vec![
+        (
+            1,
+            vec![
+                text(surprised),
+                U16(2),
+                U32(402),
+                U32(64021),
+                U16(501),
+                U16(502),
+                U16(0),
+            ],
+        ),
+        (
+            50,
+            vec![
+                text(sit),
+                U16(1),
+                U32(403),
+                U32(64050),
+                U16(0),
+                U16(0),
+                U16(0),
+            ],
+        ),
+    ]
+}
+
+fn sheets() -> Vec<Sheet> {
+    vec![
+        Sheet {
+            name: "Emote",
+            pages: vec![0, 50],
+            languages: vec![
+                (ExcelLanguage::Japanese, emote_rows("おどろく", "座る")),
+                (ExcelLanguage::English, emote_rows("Surprised", "Sit")),
+            ],
+        },
+        Sheet {
+            name: "LogMessage",
+            pages: vec![0],
+            languages: vec![
+                (
+                    ExcelLanguage::Japanese,
+                    vec![
+                        (501, vec![U16(29), if_you("はおどろいた。")]),
+                        (502, vec![U16(29), if_you("はおどろいた。")]),
+                    ],
+                ),
+                (
+                    ExcelLanguage::English,
+                    vec![
+                        (501, vec![U16(29), if_you(" look surprised.")]),
+                        (502, vec![U16(29), if_you(", surprised.")]),
+                    ],
+                ),
+            ],
+        },
+        Sheet {
+            name: "TextCommand",
+            pages: vec![0],
+            languages: vec![
+                (
+                    ExcelLanguage::Japanese,
+                    vec![
+                        (
+                            402,
+                            vec![
+                                text("/おどろく"),
+                                text(""),
+                                text("/おどろく"),
+                                text(""),
+                                text("/おど"),
+                            ],
+                        ),
+                        (
+                            403,
+                            vec![text("/すわる"), text(""), text(""), text(""), text("")],
+                        ),
+                    ],
+                ),
+                (
+                    ExcelLanguage::English,
+                    vec![
+                        (
+                            402,
+                            vec![
+                                text("/surprised"),
+                                text("/sp"),
+                                text("USAGE: /surprised"),
+                                text(""),
+                                text(""),
+                            ],
+                        ),
+                        (
+                            403,
+                            vec![text("/sit"), text(""), text(""), text(""), text("")],
+                        ),
+                    ],
+                ),
+            ],
+        },
+        // not localized, to check that such sheets are read for every language
+        Sheet {
+            name: "EmoteCategory",
+            pages: vec![0],
+            languages: vec![(
+                ExcelLanguage::None,
+                vec![(1, vec![text("General")]), (2, vec![text("Expressions")])],
+            )],
+        },
+    ]
+}
+
+fn columns() -> SheetColumns {
+    let columns = |names: &[&str]| {
+        names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.to_string(), i))
+            .collect()
+    };
+    SheetColumns {
+        emote: columns(&[
+            "Name",
+            "EmoteCategory",
+            "TextCommand",
+            "Icon",
+            "LogMessage{Targeted}",
+            "LogMessage{Untargeted}",
+            "UnlockLink",
+        ]),
+        log_message: columns(&["LogKind", "Text"]),
+        text_command: columns(&[
+            "Command",
+            "ShortCommand",
+            "Description",
+            "Alias",
+            "ShortAlias",
+        ]),
+        emote_category: columns(&["Name"]),
+    }
+}
+
+/// Writes the fixture to a fresh temporary game directory, removed when dropped.
+struct GameDir(std::path::PathBuf);
+
+impl GameDir {
+    fn new(test: &str) -> std::io::Result<GameDir> {
+        let dir =
+            std::env::temp_dir().join(format!("xiv-emote-sqpack-{}-{}", test, std::process::id()));
+        write_sqpack(&dir, &sheets())?;
+        Ok(GameDir(dir))
+    }
+}
+
+impl Drop for GameDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+#[test]
+fn can_read_sheets() -> Result<(), Box<dyn std::error::Error>> {
+    let game_dir = GameDir::new("read")?;
+    let sqpack = SqPack::open(&game_dir.0)?;
+    assert!(sqpack.exd().contains("exd/emote.exh"));
+    let emote = ExcelSheet::read(sqpack.exd(), "Emote", ExcelLanguage::Japanese)?;
+    assert_eq!(emote.header.pages.len(), 2);
+    assert_eq!(emote.rows.keys().copied().collect::<Vec<_>>(), vec![1, 50]);
+    assert_eq!(emote.rows[&50][0].to_text(), "座る");
+    assert_eq!(emote.rows[&1][3], Value::UInt(64021));
+    let log_message = ExcelSheet::read(sqpack.exd(), "LogMessage", ExcelLanguage::English)?;
+    assert_eq!(
+        log_message.rows[&502][1].to_text(),
+        "<If(Equal(ObjectParameter(1),ObjectParameter(2)))>you<Else/>ObjectParameter(2)</If>, surprised."
+    );
+    // falls back to the sheet shared by every language
+    let category = ExcelSheet::read(sqpack.exd(), "EmoteCategory", ExcelLanguage::Korean)?;
+    assert_eq!(category.rows[&2][0].to_text(), "Expressions");
+    assert!(ExcelSheet::read(sqpack.exd(), "Emote", ExcelLanguage::Korean).is_err());
+    Ok(())
+}
+
+#[test]
+fn can_load_repository() -> Result<(), Box<dyn std::error::Error>> {
+    let game_dir = GameDir::new("load")?;
+    let repo = LogMessageRepository::from_sqpack_with_columns(&game_dir.0, &columns())?;
+    let surprised = repo.emote_by_id(1)?;
+    assert_eq!(surprised.name, "Surprised");
+    assert_eq!(surprised.name_in(Language::Ja), Some("おどろく"));
+    assert_eq!(
+        surprised.commands,
+        vec!["/surprised", "/sp", "/おどろく", "/おど"]
+    );
+    assert_eq!(surprised.icon, Some(64021));
+    assert_eq!(surprised.log_kind, Some(29));
+    assert_eq!(
+        surprised
+            .category
+            .as_ref()
+            .and_then(|category| category.name_in(Language::Ja)),
+        Some("Expressions")
+    );
+    assert!(repo
+        .targeted("/sp", Language::Ja)?
+        .ends_with("はおどろいた。"));
+    assert_eq!(repo.messages("/sit")?.en, None);
+    assert!(repo.validate().is_ok());
+    Ok(())
+}
+
+#[test]
+fn missing_columns_are_reported() -> Result<(), Box<dyn std::error::Error>> {
+    let game_dir = GameDir::new("columns")?;
+    // the default layout has far more columns than the fixture
+    let res = LogMessageRepository::from_sqpack(&game_dir.0);
+    assert!(
+        matches!(
+            &res,
+            Err(LogMessageRepositoryError::InvalidSheet { sheet: "Emote", reason })
+                if reason.starts_with("missing column")
+        ),
+        "expected missing column, got {:?}",
+        res
+    );
+    Ok(())
+}
+
+#[test]
+fn missing_game_files_are_reported() {
+    let res = LogMessageRepository::from_sqpack(std::env::temp_dir().join("xiv-emote-no-game"));
+    assert!(
+        matches!(res, Err(LogMessageRepositoryError::SqPack(_))),
+        "expected sqpack error, got {:?}",
+        res
+    );
+}
+
+#[test]
+fn out_of_range_index_entries_are_reported() -> Result<(), Box<dyn std::error::Error>> {
+    let game_dir = GameDir::new("index-range")?;
+    let index_path = game_dir.0.join("sqpack/ffxiv/0a0000.win32.index");
+    let mut index = std::fs::read(&index_path)?;
+    // entries size in the index header, pushing the end of the entries past u32::MAX
+    index[0x40C..0x410].copy_from_slice(&u32::MAX.to_le_bytes());
+    std::fs::write(&index_path, index)?;
+    let res = SqPack::open(&game_dir.0);
+    assert!(
+        matches!(res, Err(SqPackError::InvalidFile { .. })),
+        "expected invalid file, got {:?}",
+        res
+    );
+    Ok(())
+}
+
+#[test]
+fn oversized_block_tables_are_reported() -> Result<(), Box<dyn std::error::Error>> {
+    let game_dir = GameDir::new("block-count")?;
+    let index = std::fs::read(game_dir.0.join("sqpack/ffxiv/0a0000.win32.index"))?;
+    let dat_path = game_dir.0.join("sqpack/ffxiv/0a0000.win32.dat0");
+    let mut dat = std::fs::read(&dat_path)?;
+    for entry in index[0x800..].chunks_exact(16) {
+        let offset = u32::from_le_bytes(entry[8..12].try_into()?) as usize * 8;
+        dat[offset + 20..offset + 24].copy_from_slice(&u32::MAX.to_le_bytes());
+    }
+    std::fs::write(&dat_path, dat)?;
+    let sqpack = SqPack::open(&game_dir.0)?;
+    let res = ExcelSheet::read(sqpack.exd(), "Emote", ExcelLanguage::English);
+    assert!(
+        matches!(res, Err(SqPackError::InvalidFile { .. })),
+        "expected invalid file, got {:?}",
+        res.map(|_| ())
+    );
+    Ok(())
+}
+
+#[test]
+fn overinflated_blocks_are_reported() -> Result<(), Box<dyn std::error::Error>> {
+    let game_dir = GameDir::new("inflate")?;
+    let index = std::fs::read(game_dir.0.join("sqpack/ffxiv/0a0000.win32.index"))?;
+    let dat_path = game_dir.0.join("sqpack/ffxiv/0a0000.win32.dat0");
+    let mut dat = std::fs::read(&dat_path)?;
+    let read_u32 = |dat: &[u8], offset: usize| {
+        u32::from_le_bytes(dat[offset..offset + 4].try_into().unwrap()) as usize
+    };
+    for entry in index[0x800..].chunks_exact(16) {
+        let offset = u32::from_le_bytes(entry[8..12].try_into()?) as usize * 8;
+        let header_size = read_u32(&dat, offset);
+        for block in 0..read_u32(&dat, offset + 20) {
+            let block = offset + header_size + read_u32(&dat, offset + 24 + block * 8);
+            // declare compressed blocks as smaller than they inflate to
+            if read_u32(&dat, block + 8) != 32000 {
+                dat[block + 12..block + 16].copy_from_slice(&1u32.to_le_bytes());
+            }
+        }
+    }
+    std::fs::write(&dat_path, dat)?;
+    let sqpack = SqPack::open(&game_dir.0)?;
+    let res = ExcelSheet::read(sqpack.exd(), "Emote", ExcelLanguage::English);
+    assert!(
+        matches!(&res, Err(SqPackError::InvalidFile { reason, .. }) if reason == "block inflates past its size"),
+        "expected invalid file, got {:?}",
+        res.map(|_| ())
+    );
+    Ok(())
+}
@@ -0,0 +1,123 @@
+#![cfg(feature = "json")]
+#![allow(clippy::result_large_err)]
+
+use xiv_emote_parser::{
+    log_message::{EmoteTextErrorKind, EmoteTextProcessErrorKind},
+    repository::{
+        Construct, Language, LogMessageRepository, LogMessageRepositoryError, MessageKind,
+    },
+};
+
+const MESSAGES: &str = r#"[
+    {
+        "id": 1,
+        "name": "Valid",
+        "commands": ["/valid"],
+        "en": {
+            "targeted": "<If(Equal(ObjectParameter(1),ObjectParameter(2)))>you<Else/>ObjectParameter(2)</If> look at them.",
+            "untargeted": "<If(Equal(ObjectParameter(1),ObjectParameter(2)))>you<Else/>ObjectParameter(2)</If> look around."
+        },
+        "ja": {
+            "targeted": "<If(Equal(ObjectParameter(1),ObjectParameter(2)))>you<Else/>ObjectParameter(2)</If>",
+            "untargeted": "<If(Equal(ObjectParameter(1),ObjectParameter(2)))>you<Else/>ObjectParameter(2)</If>"
+        }
+    },
+    {
+        "id": 2,
+        "name": "Broken",
+        "commands": ["/broken"],
+        "en": { "targeted": "<If(Equal(ObjectParameter(1)))>a</If>", "untargeted": "<Sheet(ObjStr,1)/>" },
+        "ja": { "targeted": "<If(PlayerParameter(99))>a<Else/>b</If>", "untargeted": "<Clickable(a,b)/>" }
+    },
+    { "id": 3, "name": "Sit", "commands": ["/sit"] }
+]"#;
+
+#[test]
+fn valid_messages_pass() -> Result<(), LogMessageRepositoryError> {
+    let repo = LogMessageRepository::from_json(MESSAGES)?;
+    let report = repo.validate();
+    assert_eq!(report.checked, 8);
+    assert!(report
+        .failures
+        .values()
+        .flatten()
+        .all(|failure| failure.id == 2));
+    Ok(())
+}
+
+#[test]
+fn groups_failures_by_kind() -> Result<(), LogMessageRepositoryError> {
+    let report = LogMessageRepository::from_json(MESSAGES)?.validate();
+    assert!(!report.is_ok());
+    assert_eq!(report.failure_count(), 4);
+    let kinds: Vec<_> = report.failures.keys().copied().collect();
+    assert_eq!(
+        kinds,
+        vec![
+            EmoteTextErrorKind::ParseError,
+            EmoteTextErrorKind::ProcessError(EmoteTextProcessErrorKind::ConditionError),
+            EmoteTextErrorKind::ProcessError(EmoteTextProcessErrorKind::DynamicTextError),
+        ]
+    );
+    let parse_failures: Vec<_> = report.failures[&EmoteTextErrorKind::ParseError]
+        .iter()
+        .map(|failure| (failure.language, failure.kind))
+        .collect();
+    assert_eq!(
+        parse_failures,
+        vec![
+            (Language::En, MessageKind::Targeted),
+            (Language::Ja, MessageKind::Untargeted)
+        ]
+    );
+    Ok(())
+}
+
+const UNKNOWN: &str = r#"[
+    {
+        "id": 4,
+        "name": "Unknown",
+        "commands": ["/unknown"],
+        "en": {
+            "targeted": "<Split(ObjectParameter(2), )/> (ok)",
+            "untargeted": "<If(Lowercase(ObjectParameter(2)))>you<Else/>ObjectParameter(2)</If>"
+        },
+        "ja": { "targeted": "あ<If(PlayerParameter(99))>a<Else/>b</If>", "untargeted": "<Clickable(ObjectParameter(2),1)/>" }
+    }
+]"#;
+
+#[test]
+fn groups_failures_by_construct() -> Result<(), LogMessageRepositoryError> {
+    let report = LogMessageRepository::from_json(MESSAGES)?.validate();
+    let constructs: Vec<_> = report.by_construct()[&Language::Ja]
+        .keys()
+        .cloned()
+        .collect();
+    assert_eq!(
+        constructs,
+        vec![
+            Construct::Syntax,
+            Construct::Condition("PlayerParameter(99)".to_string())
+        ]
+    );
+    let report = LogMessageRepository::from_json(UNKNOWN)?.validate();
+    let by_construct = report.by_construct();
+    let en: Vec<_> = by_construct[&Language::En].keys().cloned().collect();
+    assert_eq!(
+        en,
+        vec![
+            Construct::UnknownTag("Split".to_string()),
+            Construct::UnknownFunction("Lowercase".to_string()),
+        ]
+    );
+    let ja: Vec<_> = by_construct[&Language::Ja].keys().cloned().collect();
+    assert_eq!(
+        ja,
+        vec![
+            Construct::Condition("PlayerParameter(99)".to_string()),
+            Construct::DynamicText("<Clickable(ObjectParameter(2),1)/>".to_string()),
+        ]
+    );
+    assert_eq!(by_construct[&Language::Ja][&ja[0]][0].name, "Unknown");
+    Ok(())
+}
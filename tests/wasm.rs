@@ -0,0 +1,73 @@
+#![cfg(feature = "wasm")]
+#![allow(clippy::result_large_err)]
+
+use xiv_emote_parser::{
+    log_message::condition::{Character, Gender},
+    wasm::{EmoteRepository, WasmError},
+};
+
+const REPO: &str = r#"[
+    {
+        "id": 1,
+        "name": "Surprised",
+        "commands": ["/surprised", "/おどろく"],
+        "en": {
+            "targeted": "<If(Equal(ObjectParameter(1),ObjectParameter(2)))>You look<Else/><If(PlayerParameter(7))><SheetEn(ObjStr,2,PlayerParameter(7),1,1)/><Else/>ObjectParameter(2)</If> looks</If> at <If(Equal(ObjectParameter(1),ObjectParameter(3)))>you<Else/><If(PlayerParameter(8))><SheetEn(ObjStr,2,PlayerParameter(8),1,1)/><Else/>ObjectParameter(3)</If></If> in surprise.",
+            "untargeted": "<If(Equal(ObjectParameter(1),ObjectParameter(2)))>You look<Else/><If(PlayerParameter(7))><SheetEn(ObjStr,2,PlayerParameter(7),1,1)/><Else/>ObjectParameter(2)</If> looks</If> surprised."
+        },
+        "ja": { "targeted": "ja targeted", "untargeted": "ja untargeted" }
+    }
+]"#;
+
+fn repo() -> EmoteRepository {
+    match EmoteRepository::from_json(REPO) {
+        Ok(repo) => repo,
+        Err(_) => panic!("couldn't load repository"),
+    }
+}
+
+#[test]
+fn can_look_up_commands() {
+    let repo = repo();
+    assert_eq!(repo.commands(), vec!["/surprised", "/おどろく"]);
+    assert!(repo.has_emote("Surprised"));
+    assert!(!repo.has_emote("/unknown"));
+}
+
+#[test]
+fn characters_accept_js_naming() -> Result<(), serde_json::Error> {
+    let character: Character =
+        serde_json::from_str(r#"{ "name": "Alice", "gender": "female", "isSelf": true }"#)?;
+    assert_eq!(
+        character,
+        Character::new("Alice", Gender::Female, true, true)
+    );
+    let character: Character =
+        serde_json::from_str(r#"{ "name": "Bob", "gender": "male", "isPc": false }"#)?;
+    assert_eq!(character, Character::new("Bob", Gender::Male, false, false));
+    Ok(())
+}
+
+#[test]
+fn can_render_characters() -> Result<(), WasmError> {
+    let repo = repo();
+    let alice = Character::new("Alice", Gender::Female, true, false);
+    let bob = Character::new("Bob", Gender::Male, false, true);
+    assert_eq!(
+        repo.render_characters("/surprised", "en", alice.clone(), None)?,
+        "Alice looks surprised."
+    );
+    assert_eq!(
+        repo.render_characters("surprised", "en", alice.clone(), Some(bob))?,
+        "Alice looks at you in surprise."
+    );
+    assert_eq!(
+        repo.render_characters("/おどろく", "ja", alice.clone(), None)?,
+        "ja untargeted"
+    );
+    assert!(matches!(
+        repo.render_characters("/surprised", "xx", alice, None),
+        Err(WasmError::UnknownLanguage(code)) if code == "xx"
+    ));
+    Ok(())
+}